@@ -7,6 +7,7 @@ use near_sdk::{
     near_bindgen,
     Promise,
     PromiseOrValue,
+    TypedPromise,
 };
 
 #[global_allocator]
@@ -19,21 +20,6 @@ const SINGLE_CALL_GAS: u64 = 200000000000000;
 #[derive(Default, BorshDeserialize, BorshSerialize)]
 pub struct CrossContract {}
 
-// One can provide a name, e.g. `ext` to use for generated methods.
-#[ext_contract(ext)]
-pub trait ExtCrossContract {
-    fn merge_sort(&self, arr: Vec<u8>) -> PromiseOrValue<Vec<u8>>;
-    fn merge(
-        &self,
-        #[callback]
-        #[serializer(borsh)]
-        data0: Vec<u8>,
-        #[callback]
-        #[serializer(borsh)]
-        data1: Vec<u8>,
-    ) -> Vec<u8>;
-}
-
 // If the name is not provided, the namespace for generated methods in derived by applying snake
 // case to the trait name, e.g. ext_status_message.
 #[ext_contract]
@@ -63,11 +49,14 @@ impl CrossContract {
         let arr0 = arr[..pivot].to_vec();
         let arr1 = arr[pivot..].to_vec();
         let prepaid_gas = env::prepaid_gas();
-        let account_id = env::current_account_id();
 
-        ext::merge_sort(arr0, &account_id, 0, prepaid_gas / 4)
-            .and(ext::merge_sort(arr1, &account_id, 0, prepaid_gas / 4))
-            .then(ext::merge(&account_id, 0, prepaid_gas / 4))
+        // `ext_self` is generated by `#[near_bindgen]` and calls the methods of this contract on
+        // the current account.
+        Self::ext_self()
+            .with_gas(prepaid_gas / 4)
+            .merge_sort(arr0)
+            .and(Self::ext_self().with_gas(prepaid_gas / 4).merge_sort(arr1))
+            .then(Self::ext_self().with_gas(prepaid_gas / 4).merge())
             .into()
     }
 
@@ -122,20 +111,21 @@ impl CrossContract {
     //    }
 
     pub fn simple_call(&mut self, account_id: String, message: String) {
-        ext_status_message::set_status(message, &account_id, 0, SINGLE_CALL_GAS);
+        ext_status_message::set_status(message).with_gas(SINGLE_CALL_GAS).on(&account_id);
     }
-    pub fn complex_call(&mut self, account_id: String, message: String) -> Promise {
+    pub fn complex_call(
+        &mut self,
+        account_id: String,
+        message: String,
+    ) -> TypedPromise<Option<String>> {
         // 1) call status_message to record a message from the signer.
         // 2) call status_message to retrieve the message of the signer.
         // 3) return that message as its own result.
         // Note, for a contract to simply call another contract (1) is sufficient.
-        ext_status_message::set_status(message, &account_id, 0, SINGLE_CALL_GAS).then(
-            ext_status_message::get_status(
-                env::signer_account_id(),
-                &account_id,
-                0,
-                SINGLE_CALL_GAS,
-            ),
+        ext_status_message::set_status(message).with_gas(SINGLE_CALL_GAS).on(&account_id).then(
+            ext_status_message::get_status(env::signer_account_id())
+                .with_gas(SINGLE_CALL_GAS)
+                .on(&account_id),
         )
     }
 
@@ -86,6 +86,24 @@ mod tests {
         assert!(contract.set_status("hi".to_string()));
     }
 
+    #[near_bindgen]
+    impl StatusMessage {
+        #[view]
+        pub fn clear_status_in_view(&mut self) {
+            self.records.remove(&env::signer_account_id());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Removing from the storage is not allowed in a view method.")]
+    fn view_method_changes_state() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = StatusMessage::default();
+        contract.set_status("hello".to_string());
+        contract.clear_status_in_view();
+    }
+
     #[test]
     fn get_nonexistent_message() {
         let context = get_context(vec![], true);
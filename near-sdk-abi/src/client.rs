@@ -0,0 +1,136 @@
+//! Generation of the `#[ext_contract]` trait that describes the contract with the given ABI. The
+//! module generated for the trait contains `Client` with a typed method for each method of the
+//! contract, see `near_sdk::client`, so the services that call the contract do not have to repeat
+//! its signatures. The types used by the contract should be in scope of the generated trait.
+use near_sdk::abi::{ArgAbi, ContractAbi, MethodAbi, SerializerType};
+
+/// Returns the source of the trait named `trait_name` with the exported methods of the contract.
+/// Callback arguments are omitted, since they are not passed by the callers. Init methods are
+/// called as methods that return nothing.
+pub fn ext_contract_trait(abi: &ContractAbi, trait_name: &str) -> String {
+    let methods: Vec<String> = abi.methods.iter().map(trait_method).collect();
+    format!("#[ext_contract(client)]\npub trait {} {{\n{}}}\n", trait_name, methods.join("\n"))
+}
+
+fn trait_method(method: &MethodAbi) -> String {
+    let mut result = String::new();
+    if method.is_payable {
+        result.push_str("    #[payable]\n");
+    }
+    if let Some(ty) = &method.result {
+        if ty.serializer != SerializerType::JSON {
+            result.push_str(&format!(
+                "    #[result_serializer({})]\n",
+                serializer_name(ty.serializer)
+            ));
+        }
+    }
+    let receiver = if method.is_view { "&self" } else { "&mut self" };
+    let args: Vec<String> =
+        std::iter::once(receiver.to_string()).chain(method.args.iter().map(trait_arg)).collect();
+    let output = match &method.result {
+        Some(ty) => format!(" -> {}", ty.type_name),
+        None => String::new(),
+    };
+    result.push_str(&format!("    fn {}({}){};\n", method.name, args.join(", "), output));
+    result
+}
+
+fn trait_arg(arg: &ArgAbi) -> String {
+    match arg.ty.serializer {
+        SerializerType::JSON => format!("{}: {}", arg.name, arg.ty.type_name),
+        serializer => format!(
+            "#[serializer({})] {}: {}",
+            serializer_name(serializer),
+            arg.name,
+            arg.ty.type_name
+        ),
+    }
+}
+
+/// The name of the serializer used in `#[serializer(...)]` attributes.
+fn serializer_name(serializer: SerializerType) -> &'static str {
+    match serializer {
+        SerializerType::JSON => "json",
+        SerializerType::Borsh => "borsh",
+        SerializerType::Raw => "raw",
+        SerializerType::MessagePack => "msgpack",
+        SerializerType::CBOR => "cbor",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::abi::TypeAbi;
+    use near_sdk_core::{ExtContractArgs, ItemTraitInfo};
+
+    fn type_abi(type_name: &str, serializer: SerializerType) -> TypeAbi {
+        TypeAbi { type_name: type_name.to_string(), serializer, schema: None }
+    }
+
+    fn method(name: &str) -> MethodAbi {
+        MethodAbi {
+            name: name.to_string(),
+            is_view: false,
+            is_init: false,
+            is_payable: false,
+            positional_args: true,
+            args: vec![],
+            callbacks: vec![],
+            callbacks_vec: None,
+            result: None,
+        }
+    }
+
+    #[test]
+    fn test_ext_contract_trait() {
+        let mut new = method("new");
+        new.is_init = true;
+        new.args = vec![ArgAbi {
+            name: "owner_id".to_string(),
+            ty: type_abi("String", SerializerType::Borsh),
+        }];
+        let mut transfer = method("transfer");
+        transfer.is_payable = true;
+        transfer.args = vec![
+            ArgAbi {
+                name: "receiver_id".to_string(),
+                ty: type_abi("String", SerializerType::JSON),
+            },
+            ArgAbi { name: "amount".to_string(), ty: type_abi("U128", SerializerType::JSON) },
+        ];
+        transfer.callbacks =
+            vec![ArgAbi { name: "result".to_string(), ty: type_abi("u64", SerializerType::JSON) }];
+        let mut balance_of = method("balance_of");
+        balance_of.is_view = true;
+        balance_of.result = Some(type_abi("Option<U128>", SerializerType::Borsh));
+
+        let source =
+            ext_contract_trait(&ContractAbi::new(vec![new, transfer, balance_of]), "Token");
+        assert_eq!(
+            source,
+            "#[ext_contract(client)]\n\
+             pub trait Token {\n    \
+                 fn new(&mut self, #[serializer(borsh)] owner_id: String);\n\n    \
+                 #[payable]\n    \
+                 fn transfer(&mut self, receiver_id: String, amount: U128);\n\n    \
+                 #[result_serializer(borsh)]\n    \
+                 fn balance_of(&self) -> Option<U128>;\n\
+             }\n"
+        );
+
+        let file = syn::parse_file(&source).unwrap();
+        match file.items.into_iter().next() {
+            Some(syn::Item::Trait(mut item_trait)) => {
+                let args: ExtContractArgs = item_trait.attrs[0].parse_args().unwrap();
+                let info = ItemTraitInfo::new(&mut item_trait, &args).unwrap();
+                assert!(info.client);
+                assert_eq!(info.mod_name.to_string(), "token");
+                assert_eq!(info.methods.len(), 3);
+                assert!(info.methods[1].attr_sig_info.is_payable);
+            }
+            _ => panic!("Expected a trait"),
+        }
+    }
+}
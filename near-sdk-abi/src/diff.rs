@@ -0,0 +1,873 @@
+//! Comparison of the metadata or the ABIs of two versions of a contract, e.g. before an upgrade.
+//! Each difference is classified as breaking, if the callers of the old version can fail or
+//! misinterpret the results when calling the new one, or as compatible otherwise.
+use borsh::schema::{BorshSchemaContainer, Declaration, Definition, Fields};
+use near_sdk::abi::{ArgAbi, ContractAbi, MethodAbi, SerializerType, TypeAbi};
+use near_sdk::{AccessRestriction, CallbackMetadata, Metadata, MethodMetadata};
+use std::collections::HashSet;
+use std::fmt;
+
+/// Whether the callers of the old version of the contract are affected by the change.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compatibility {
+    Compatible,
+    Breaking,
+}
+
+/// A difference between two versions of a method.
+#[derive(Debug, PartialEq)]
+pub enum ChangeKind {
+    MethodAdded,
+    MethodRemoved,
+    /// A new argument is expected.
+    ArgAdded {
+        name: String,
+        type_name: Declaration,
+    },
+    /// The argument is no longer read.
+    ArgRemoved {
+        name: String,
+    },
+    /// The argument has a different type.
+    ArgTypeChanged {
+        name: String,
+        old: Declaration,
+        new: Declaration,
+    },
+    /// The same arguments are expected in a different order, which matters for Borsh and for the
+    /// JSON arguments passed as an array.
+    ArgsReordered,
+    /// The JSON arguments can also be passed as an array.
+    PositionalArgsAdded,
+    /// The JSON arguments can no longer be passed as an array.
+    PositionalArgsRemoved,
+    InputSerializerChanged {
+        old: SerializerType,
+        new: SerializerType,
+    },
+    /// The method returns a value of a different type, `None` if it returns nothing.
+    ResultChanged {
+        old: Option<Declaration>,
+        new: Option<Declaration>,
+    },
+    ResultSerializerChanged {
+        old: SerializerType,
+        new: SerializerType,
+    },
+    /// The number, the types or the serializers of the callbacks changed.
+    CallbacksChanged,
+    ViewToCall,
+    CallToView,
+    PayableAdded,
+    PayableRemoved,
+    /// The method became an init method or stopped being one.
+    InitChanged {
+        is_init: bool,
+    },
+    AccessChanged {
+        old: AccessRestriction,
+        new: AccessRestriction,
+    },
+}
+
+/// A difference between the two versions of the contract.
+#[derive(Debug, PartialEq)]
+pub struct Change {
+    /// The name of the method.
+    pub method: String,
+    pub kind: ChangeKind,
+    pub compatibility: Compatibility,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let compatibility = match self.compatibility {
+            Compatibility::Compatible => "compatible",
+            Compatibility::Breaking => "breaking",
+        };
+        write!(f, "{}: `{}` ", compatibility, self.method)?;
+        match &self.kind {
+            ChangeKind::MethodAdded => write!(f, "is added"),
+            ChangeKind::MethodRemoved => write!(f, "is removed"),
+            ChangeKind::ArgAdded { name, type_name } => {
+                write!(f, "takes new argument `{}: {}`", name, type_name)
+            }
+            ChangeKind::ArgRemoved { name } => write!(f, "no longer takes argument `{}`", name),
+            ChangeKind::ArgTypeChanged { name, old, new } => {
+                write!(f, "argument `{}` changed type from `{}` to `{}`", name, old, new)
+            }
+            ChangeKind::ArgsReordered => write!(f, "takes the arguments in a different order"),
+            ChangeKind::PositionalArgsAdded => write!(f, "accepts the arguments as an array"),
+            ChangeKind::PositionalArgsRemoved => {
+                write!(f, "no longer accepts the arguments as an array")
+            }
+            ChangeKind::InputSerializerChanged { old, new } => {
+                write!(f, "arguments changed serializer from {:?} to {:?}", old, new)
+            }
+            ChangeKind::ResultChanged { old, new } => write!(
+                f,
+                "result changed type from `{}` to `{}`",
+                old.as_deref().unwrap_or("()"),
+                new.as_deref().unwrap_or("()")
+            ),
+            ChangeKind::ResultSerializerChanged { old, new } => {
+                write!(f, "result changed serializer from {:?} to {:?}", old, new)
+            }
+            ChangeKind::CallbacksChanged => write!(f, "callbacks changed"),
+            ChangeKind::ViewToCall => write!(f, "is no longer a view method"),
+            ChangeKind::CallToView => write!(f, "became a view method"),
+            ChangeKind::PayableAdded => write!(f, "became payable"),
+            ChangeKind::PayableRemoved => write!(f, "is no longer payable"),
+            ChangeKind::InitChanged { is_init: true } => write!(f, "became an init method"),
+            ChangeKind::InitChanged { is_init: false } => write!(f, "is no longer an init method"),
+            ChangeKind::AccessChanged { old, new } => {
+                write!(f, "access changed from {:?} to {:?}", old, new)
+            }
+        }
+    }
+}
+
+/// Returns the differences between the methods of the old and the new version of the contract.
+/// Doc comments are not compared.
+pub fn diff(old: &Metadata, new: &Metadata) -> Vec<Change> {
+    diff_methods(&old.methods, &new.methods, |m| &m.name, diff_method)
+}
+
+/// Returns the differences between the methods of the old and the new version of the contract,
+/// described by their ABIs, e.g. the ones extracted from the source with `extract_abi`. Such ABIs
+/// have no schemas, so the types are compared by their names, and renaming a type is breaking. The
+/// access restrictions are not recorded in the ABI, so their changes are not detected.
+pub fn diff_abi(old: &ContractAbi, new: &ContractAbi) -> Vec<Change> {
+    diff_methods(&old.methods, &new.methods, |m| &m.name, diff_method_abi)
+}
+
+/// Returns `true` if any of the changes is breaking.
+pub fn is_breaking(changes: &[Change]) -> bool {
+    changes.iter().any(|change| change.compatibility == Compatibility::Breaking)
+}
+
+/// Matches the methods of the two versions by their names.
+fn diff_methods<M>(
+    old: &[M],
+    new: &[M],
+    name: impl Fn(&M) -> &String,
+    diff_method: impl Fn(&M, &M) -> Vec<(ChangeKind, Compatibility)>,
+) -> Vec<Change> {
+    let mut changes = vec![];
+    for old_method in old {
+        let method = name(old_method).clone();
+        match new.iter().find(|m| name(m) == name(old_method)) {
+            Some(new_method) => {
+                for (kind, compatibility) in diff_method(old_method, new_method) {
+                    changes.push(Change { method: method.clone(), kind, compatibility });
+                }
+            }
+            None => changes.push(Change {
+                method,
+                kind: ChangeKind::MethodRemoved,
+                compatibility: Compatibility::Breaking,
+            }),
+        }
+    }
+    for new_method in new {
+        if !old.iter().any(|m| name(m) == name(new_method)) {
+            changes.push(Change {
+                method: name(new_method).clone(),
+                kind: ChangeKind::MethodAdded,
+                compatibility: Compatibility::Compatible,
+            });
+        }
+    }
+    changes
+}
+
+fn diff_method(old: &MethodMetadata, new: &MethodMetadata) -> Vec<(ChangeKind, Compatibility)> {
+    use Compatibility::*;
+    let mut changes = diff_flags(
+        (old.is_view, old.is_payable, old.is_init),
+        (new.is_view, new.is_payable, new.is_init),
+    );
+    if old.access != new.access {
+        let compatibility = match new.access {
+            AccessRestriction::Public => Compatible,
+            AccessRestriction::SelfOnly => Breaking,
+        };
+        changes
+            .push((ChangeKind::AccessChanged { old: old.access, new: new.access }, compatibility));
+    }
+    // The serializer does not matter for the methods without arguments.
+    let has_args = old.args.is_some() || new.args.is_some();
+    if old.input_serializer != new.input_serializer && has_args {
+        changes.push((
+            ChangeKind::InputSerializerChanged {
+                old: old.input_serializer,
+                new: new.input_serializer,
+            },
+            Breaking,
+        ));
+    } else {
+        let old_args = old.args.as_ref().map(input_fields).unwrap_or_default();
+        let new_args = new.args.as_ref().map(input_fields).unwrap_or_default();
+        let (old_schema, new_schema) = (old.args.as_ref(), new.args.as_ref());
+        changes.extend(diff_args(
+            &old_args,
+            &new_args,
+            new.input_serializer == SerializerType::JSON,
+            (old.positional_args, new.positional_args),
+            |_, old_decl, new_decl| {
+                same_type(old_schema.unwrap(), old_decl, new_schema.unwrap(), new_decl)
+            },
+        ));
+    }
+    if old.result_serializer != new.result_serializer
+        && old.result.is_some()
+        && new.result.is_some()
+    {
+        changes.push((
+            ChangeKind::ResultSerializerChanged {
+                old: old.result_serializer,
+                new: new.result_serializer,
+            },
+            Breaking,
+        ));
+    } else {
+        changes.extend(diff_result(
+            old.result.as_ref(),
+            new.result.as_ref(),
+            |old, new| same_type(old, &old.declaration, new, &new.declaration),
+            |schema| schema.declaration.clone(),
+        ));
+    }
+    let same_callbacks = old.callbacks.len() == new.callbacks.len()
+        && old.callbacks.iter().zip(&new.callbacks).all(|(old, new)| same_callback(old, new))
+        && match (&old.callbacks_vec, &new.callbacks_vec) {
+            (None, None) => true,
+            (Some(old), Some(new)) => same_callback(old, new),
+            _ => false,
+        };
+    // The promises created by the old version can still be resolved into the new one.
+    if !same_callbacks {
+        changes.push((ChangeKind::CallbacksChanged, Breaking));
+    }
+    changes
+}
+
+fn diff_method_abi(old: &MethodAbi, new: &MethodAbi) -> Vec<(ChangeKind, Compatibility)> {
+    use Compatibility::*;
+    let mut changes = diff_flags(
+        (old.is_view, old.is_payable, old.is_init),
+        (new.is_view, new.is_payable, new.is_init),
+    );
+    let input_serializer = |method: &MethodAbi| method.args.first().map(|arg| arg.ty.serializer);
+    match (input_serializer(old), input_serializer(new)) {
+        (Some(old), Some(new)) if old != new => {
+            changes.push((ChangeKind::InputSerializerChanged { old, new }, Breaking))
+        }
+        (old_serializer, new_serializer) => {
+            let is_json = new_serializer.or(old_serializer).unwrap_or(SerializerType::JSON)
+                == SerializerType::JSON;
+            let args = |method: &MethodAbi| -> Vec<(String, Declaration)> {
+                method.args.iter().map(|arg| (arg.name.clone(), arg.ty.type_name.clone())).collect()
+            };
+            fn arg_type<'a>(method: &'a MethodAbi, name: &str) -> &'a TypeAbi {
+                &method.args.iter().find(|arg| arg.name == name).unwrap().ty
+            }
+            changes.extend(diff_args(
+                &args(old),
+                &args(new),
+                is_json,
+                (old.positional_args, new.positional_args),
+                |name, _, _| same_type_abi(arg_type(old, name), arg_type(new, name)),
+            ));
+        }
+    }
+    match (&old.result, &new.result) {
+        (Some(old), Some(new)) if old.serializer != new.serializer => changes.push((
+            ChangeKind::ResultSerializerChanged { old: old.serializer, new: new.serializer },
+            Breaking,
+        )),
+        (old, new) => {
+            changes.extend(diff_result(old.as_ref(), new.as_ref(), same_type_abi, |ty| {
+                ty.type_name.clone()
+            }))
+        }
+    }
+    let same_arg = |old: &ArgAbi, new: &ArgAbi| same_type_abi(&old.ty, &new.ty);
+    let same_callbacks = old.callbacks.len() == new.callbacks.len()
+        && old.callbacks.iter().zip(&new.callbacks).all(|(old, new)| same_arg(old, new))
+        && match (&old.callbacks_vec, &new.callbacks_vec) {
+            (None, None) => true,
+            (Some(old), Some(new)) => same_arg(old, new),
+            _ => false,
+        };
+    if !same_callbacks {
+        changes.push((ChangeKind::CallbacksChanged, Breaking));
+    }
+    changes
+}
+
+/// Compares whether the method is a view, a payable and an init method.
+fn diff_flags(
+    (old_view, old_payable, old_init): (bool, bool, bool),
+    (new_view, new_payable, new_init): (bool, bool, bool),
+) -> Vec<(ChangeKind, Compatibility)> {
+    use Compatibility::*;
+    let mut changes = vec![];
+    match (old_view, new_view) {
+        // View calls of the method would fail.
+        (true, false) => changes.push((ChangeKind::ViewToCall, Breaking)),
+        (false, true) => changes.push((ChangeKind::CallToView, Compatible)),
+        _ => {}
+    }
+    match (old_payable, new_payable) {
+        (false, true) => changes.push((ChangeKind::PayableAdded, Compatible)),
+        // Calls with attached deposit would fail.
+        (true, false) => changes.push((ChangeKind::PayableRemoved, Breaking)),
+        _ => {}
+    }
+    if old_init != new_init {
+        changes.push((ChangeKind::InitChanged { is_init: new_init }, Breaking));
+    }
+    changes
+}
+
+/// Compares the arguments read from the input by their names, and by their positions if the callers
+/// of the old version can pass them positionally, i.e. with Borsh or as a JSON array.
+fn diff_args(
+    old_args: &[(String, Declaration)],
+    new_args: &[(String, Declaration)],
+    is_json: bool,
+    (old_positional, new_positional): (bool, bool),
+    same_type: impl Fn(&str, &str, &str) -> bool,
+) -> Vec<(ChangeKind, Compatibility)> {
+    let by_position = !is_json || old_positional;
+    let mut changes = vec![];
+    for (name, old_decl) in old_args {
+        match new_args.iter().find(|(new_name, _)| new_name == name) {
+            Some((_, new_decl)) => {
+                if !same_type(name, old_decl, new_decl) {
+                    changes.push((
+                        ChangeKind::ArgTypeChanged {
+                            name: name.clone(),
+                            old: old_decl.clone(),
+                            new: new_decl.clone(),
+                        },
+                        Compatibility::Breaking,
+                    ));
+                }
+            }
+            // Unknown fields are ignored by JSON, but the Borsh input would have trailing bytes
+            // and the JSON array an extra element.
+            None => changes.push((
+                ChangeKind::ArgRemoved { name: name.clone() },
+                if by_position { Compatibility::Breaking } else { Compatibility::Compatible },
+            )),
+        }
+    }
+    for (index, (name, new_decl)) in new_args.iter().enumerate() {
+        if !old_args.iter().any(|(old_name, _)| old_name == name) {
+            // Missing optional fields are deserialized from JSON as `None`, but only the trailing
+            // elements can be missing from an array.
+            let is_optional = is_json
+                && new_decl.starts_with("Option<")
+                && (!by_position || index >= old_args.len());
+            changes.push((
+                ChangeKind::ArgAdded { name: name.clone(), type_name: new_decl.clone() },
+                if is_optional { Compatibility::Compatible } else { Compatibility::Breaking },
+            ));
+        }
+    }
+    // Borsh arguments and JSON arrays are positional, so reordering them is breaking as well.
+    if by_position && changes.is_empty() {
+        let old_names: Vec<_> = old_args.iter().map(|(name, _)| name).collect();
+        let new_names: Vec<_> = new_args.iter().map(|(name, _)| name).collect();
+        if old_names != new_names {
+            changes.push((ChangeKind::ArgsReordered, Compatibility::Breaking));
+        }
+    }
+    // The flag does not matter for the methods without arguments.
+    if is_json && !(old_args.is_empty() && new_args.is_empty()) {
+        match (old_positional, new_positional) {
+            (false, true) => {
+                changes.push((ChangeKind::PositionalArgsAdded, Compatibility::Compatible))
+            }
+            // Calls with the arguments passed as an array would fail.
+            (true, false) => {
+                changes.push((ChangeKind::PositionalArgsRemoved, Compatibility::Breaking))
+            }
+            _ => {}
+        }
+    }
+    changes
+}
+
+fn diff_result<T>(
+    old: Option<&T>,
+    new: Option<&T>,
+    same_type: impl Fn(&T, &T) -> bool,
+    type_name: impl Fn(&T) -> Declaration,
+) -> Vec<(ChangeKind, Compatibility)> {
+    let compatibility = match (old, new) {
+        (None, None) => return vec![],
+        (Some(old), Some(new)) if same_type(old, new) => return vec![],
+        // The callers of the old version ignore the result.
+        (None, Some(_)) => Compatibility::Compatible,
+        _ => Compatibility::Breaking,
+    };
+    let kind = ChangeKind::ResultChanged { old: old.map(&type_name), new: new.map(&type_name) };
+    vec![(kind, compatibility)]
+}
+
+fn same_callback(old: &CallbackMetadata, new: &CallbackMetadata) -> bool {
+    old.serializer == new.serializer
+        && same_type(&old.schema, &old.schema.declaration, &new.schema, &new.schema.declaration)
+}
+
+/// Whether the types in the ABIs are serialized the same way. They are compared by their JSON
+/// Schemas if both have them, and by their names otherwise.
+fn same_type_abi(old: &TypeAbi, new: &TypeAbi) -> bool {
+    old.serializer == new.serializer
+        && match (&old.schema, &new.schema) {
+            (Some(old_schema), Some(new_schema)) => old_schema == new_schema,
+            _ => old.type_name == new.type_name,
+        }
+}
+
+/// The names and the types of the fields of the `Input` struct that describes the arguments.
+fn input_fields(schema: &BorshSchemaContainer) -> Vec<(String, Declaration)> {
+    match schema.definitions.get(&schema.declaration) {
+        Some(Definition::Struct { fields: Fields::NamedFields(fields) }) => fields.clone(),
+        _ => vec![],
+    }
+}
+
+/// Whether the types are serialized the same way. The names of the structs and the enums are not
+/// compared, so renaming a type is compatible, but the names of their fields and variants are.
+fn same_type(
+    old: &BorshSchemaContainer,
+    old_decl: &str,
+    new: &BorshSchemaContainer,
+    new_decl: &str,
+) -> bool {
+    TypeComparison { old, new, visited: HashSet::new() }.same(old_decl, new_decl)
+}
+
+struct TypeComparison<'a> {
+    old: &'a BorshSchemaContainer,
+    new: &'a BorshSchemaContainer,
+    /// Pairs of the declarations that are being compared, so that recursive types terminate.
+    visited: HashSet<(String, String)>,
+}
+
+impl<'a> TypeComparison<'a> {
+    fn same(&mut self, old_decl: &str, new_decl: &str) -> bool {
+        if !self.visited.insert((old_decl.to_string(), new_decl.to_string())) {
+            return true;
+        }
+        let (old, new) = (self.old.definitions.get(old_decl), self.new.definitions.get(new_decl));
+        match (old, new) {
+            // Primitive types.
+            (None, None) => old_decl == new_decl,
+            (
+                Some(Definition::Array { length: old_length, elements: old_elements }),
+                Some(Definition::Array { length: new_length, elements: new_elements }),
+            ) => old_length == new_length && self.same(old_elements, new_elements),
+            (
+                Some(Definition::Sequence { elements: old_elements }),
+                Some(Definition::Sequence { elements: new_elements }),
+            ) => self.same(old_elements, new_elements),
+            (
+                Some(Definition::Tuple { elements: old_elements }),
+                Some(Definition::Tuple { elements: new_elements }),
+            ) => self.same_all(old_elements, new_elements),
+            (
+                Some(Definition::Enum { variants: old_variants }),
+                Some(Definition::Enum { variants: new_variants }),
+            ) => self.same_named(old_variants, new_variants),
+            (
+                Some(Definition::Struct { fields: old_fields }),
+                Some(Definition::Struct { fields: new_fields }),
+            ) => match (old_fields, new_fields) {
+                (Fields::NamedFields(old_fields), Fields::NamedFields(new_fields)) => {
+                    self.same_named(old_fields, new_fields)
+                }
+                (Fields::UnnamedFields(old_fields), Fields::UnnamedFields(new_fields)) => {
+                    self.same_all(old_fields, new_fields)
+                }
+                (Fields::Empty, Fields::Empty) => true,
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    fn same_all(&mut self, old: &[Declaration], new: &[Declaration]) -> bool {
+        old.len() == new.len() && old.iter().zip(new).all(|(old, new)| self.same(old, new))
+    }
+
+    fn same_named(&mut self, old: &[(String, Declaration)], new: &[(String, Declaration)]) -> bool {
+        old.len() == new.len()
+            && old.iter().zip(new).all(|((old_name, old), (new_name, new))| {
+                old_name == new_name && self.same(old, new)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use borsh::BorshSchema;
+    use near_sdk::json_types::U128;
+    use std::collections::HashMap;
+
+    fn method(name: &str) -> MethodMetadata {
+        MethodMetadata {
+            name: name.to_string(),
+            docs: None,
+            is_view: false,
+            is_init: false,
+            is_payable: false,
+            access: AccessRestriction::Public,
+            input_serializer: SerializerType::JSON,
+            positional_args: true,
+            args: None,
+            callbacks: vec![],
+            callbacks_vec: None,
+            result_serializer: SerializerType::JSON,
+            result: None,
+        }
+    }
+
+    /// Schema of the `Input` struct with the given fields.
+    fn input(fields: Vec<(&str, BorshSchemaContainer)>) -> Option<BorshSchemaContainer> {
+        let mut definitions = HashMap::new();
+        let mut named_fields = vec![];
+        for (name, container) in fields {
+            named_fields.push((name.to_string(), container.declaration));
+            definitions.extend(container.definitions);
+        }
+        definitions.insert(
+            "Input".to_string(),
+            Definition::Struct { fields: Fields::NamedFields(named_fields) },
+        );
+        Some(BorshSchemaContainer { declaration: "Input".to_string(), definitions })
+    }
+
+    fn changes(old: MethodMetadata, new: MethodMetadata) -> Vec<(ChangeKind, Compatibility)> {
+        diff(&Metadata::new(vec![old]), &Metadata::new(vec![new]))
+            .into_iter()
+            .map(|change| (change.kind, change.compatibility))
+            .collect()
+    }
+
+    #[derive(BorshSchema)]
+    #[allow(dead_code)]
+    struct Transfer {
+        receiver_id: String,
+        amount: U128,
+    }
+
+    #[derive(BorshSchema)]
+    #[allow(dead_code)]
+    struct Payment {
+        receiver_id: String,
+        amount: U128,
+    }
+
+    #[derive(BorshSchema)]
+    #[allow(dead_code)]
+    struct TransferV2 {
+        receiver_id: String,
+        amount: U128,
+        memo: Option<String>,
+    }
+
+    #[test]
+    fn test_added_and_removed_methods() {
+        let old = Metadata::new(vec![method("a"), method("b")]);
+        let new = Metadata::new(vec![method("b"), method("c")]);
+        let changes = diff(&old, &new);
+        assert_eq!(
+            changes.iter().map(|change| change.to_string()).collect::<Vec<_>>(),
+            vec!["breaking: `a` is removed", "compatible: `c` is added"]
+        );
+        assert!(is_breaking(&changes));
+        assert!(diff(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn test_flags() {
+        let mut old = method("a");
+        old.is_view = true;
+        let mut new = method("a");
+        new.is_payable = true;
+        new.access = AccessRestriction::SelfOnly;
+        assert_eq!(
+            changes(old, new),
+            vec![
+                (ChangeKind::ViewToCall, Compatibility::Breaking),
+                (ChangeKind::PayableAdded, Compatibility::Compatible),
+                (
+                    ChangeKind::AccessChanged {
+                        old: AccessRestriction::Public,
+                        new: AccessRestriction::SelfOnly
+                    },
+                    Compatibility::Breaking
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_args() {
+        let mut old = method("a");
+        old.positional_args = false;
+        old.args = input(vec![
+            ("transfer", Transfer::schema_container()),
+            ("amount", u64::schema_container()),
+            ("memo", String::schema_container()),
+        ]);
+        let mut new = method("a");
+        new.positional_args = false;
+        new.args = input(vec![
+            // Renaming the struct does not change its serialization.
+            ("transfer", Payment::schema_container()),
+            ("amount", U128::schema_container()),
+            ("gas", Option::<u64>::schema_container()),
+        ]);
+        assert_eq!(
+            changes(old, new),
+            vec![
+                (
+                    ChangeKind::ArgTypeChanged {
+                        name: "amount".to_string(),
+                        old: "u64".to_string(),
+                        new: "U128".to_string()
+                    },
+                    Compatibility::Breaking
+                ),
+                (ChangeKind::ArgRemoved { name: "memo".to_string() }, Compatibility::Compatible),
+                (
+                    ChangeKind::ArgAdded {
+                        name: "gas".to_string(),
+                        type_name: "Option<u64>".to_string()
+                    },
+                    Compatibility::Compatible
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_borsh_args() {
+        let mut old = method("a");
+        old.input_serializer = SerializerType::Borsh;
+        old.args = input(vec![("a", u64::schema_container()), ("b", u64::schema_container())]);
+        let mut new = method("a");
+        new.input_serializer = SerializerType::Borsh;
+        new.args = input(vec![("b", u64::schema_container()), ("a", u64::schema_container())]);
+        assert_eq!(changes(old, new), vec![(ChangeKind::ArgsReordered, Compatibility::Breaking)]);
+
+        let mut old = method("a");
+        old.args = input(vec![("a", u64::schema_container())]);
+        let mut new = method("a");
+        new.input_serializer = SerializerType::Borsh;
+        new.args = input(vec![]);
+        assert_eq!(
+            changes(old, new),
+            vec![(
+                ChangeKind::InputSerializerChanged {
+                    old: SerializerType::JSON,
+                    new: SerializerType::Borsh
+                },
+                Compatibility::Breaking
+            )]
+        );
+    }
+
+    #[test]
+    fn test_positional_args() {
+        let args = |names: &[&str]| {
+            input(names.iter().map(|name| (*name, u64::schema_container())).collect())
+        };
+        let mut old = method("a");
+        old.args = args(&["a", "b"]);
+        let mut new = method("a");
+        new.args = args(&["b", "a"]);
+        assert_eq!(changes(old, new), vec![(ChangeKind::ArgsReordered, Compatibility::Breaking)]);
+
+        // Only the trailing optional arguments can be omitted from an array.
+        let mut old = method("a");
+        old.args = args(&["a", "b"]);
+        let mut new = method("a");
+        new.args = input(vec![
+            ("memo", Option::<String>::schema_container()),
+            ("a", u64::schema_container()),
+            ("b", u64::schema_container()),
+            ("gas", Option::<u64>::schema_container()),
+        ]);
+        assert_eq!(
+            changes(old, new),
+            vec![
+                (
+                    ChangeKind::ArgAdded {
+                        name: "memo".to_string(),
+                        type_name: "Option<string>".to_string()
+                    },
+                    Compatibility::Breaking
+                ),
+                (
+                    ChangeKind::ArgAdded {
+                        name: "gas".to_string(),
+                        type_name: "Option<u64>".to_string()
+                    },
+                    Compatibility::Compatible
+                ),
+            ]
+        );
+
+        let mut old = method("a");
+        old.args = args(&["a", "b"]);
+        let mut new = method("a");
+        new.positional_args = false;
+        new.args = args(&["b"]);
+        assert_eq!(
+            changes(old, new),
+            vec![
+                (ChangeKind::ArgRemoved { name: "a".to_string() }, Compatibility::Breaking),
+                (ChangeKind::PositionalArgsRemoved, Compatibility::Breaking),
+            ]
+        );
+
+        let mut old = method("a");
+        old.positional_args = false;
+        old.args = args(&["a", "b"]);
+        let mut new = method("a");
+        new.args = args(&["b", "a"]);
+        assert_eq!(
+            changes(old, new),
+            vec![(ChangeKind::PositionalArgsAdded, Compatibility::Compatible)]
+        );
+
+        // The flag does not matter without arguments.
+        let mut old = method("a");
+        old.positional_args = false;
+        assert!(changes(old, method("a")).is_empty());
+    }
+
+    #[test]
+    fn test_result() {
+        let mut old = method("a");
+        old.result = Some(Transfer::schema_container());
+        let mut new = method("a");
+        new.result = Some(TransferV2::schema_container());
+        assert_eq!(
+            changes(old, new),
+            vec![(
+                ChangeKind::ResultChanged {
+                    old: Some("Transfer".to_string()),
+                    new: Some("TransferV2".to_string())
+                },
+                Compatibility::Breaking
+            )]
+        );
+
+        let old = method("a");
+        let mut new = method("a");
+        new.result = Some(u64::schema_container());
+        assert_eq!(changes(old, new)[0].1, Compatibility::Compatible);
+    }
+
+    #[test]
+    fn test_callbacks() {
+        let mut old = method("a");
+        old.callbacks = vec![CallbackMetadata {
+            serializer: SerializerType::JSON,
+            schema: u64::schema_container(),
+        }];
+        let mut new = method("a");
+        new.callbacks = vec![CallbackMetadata {
+            serializer: SerializerType::Borsh,
+            schema: u64::schema_container(),
+        }];
+        assert_eq!(
+            changes(old, new),
+            vec![(ChangeKind::CallbacksChanged, Compatibility::Breaking)]
+        );
+    }
+
+    fn method_abi(name: &str) -> MethodAbi {
+        MethodAbi {
+            name: name.to_string(),
+            is_view: false,
+            is_init: false,
+            is_payable: false,
+            positional_args: true,
+            args: vec![],
+            callbacks: vec![],
+            callbacks_vec: None,
+            result: None,
+        }
+    }
+
+    fn arg_abi(name: &str, type_name: &str, serializer: SerializerType) -> ArgAbi {
+        ArgAbi {
+            name: name.to_string(),
+            ty: TypeAbi { type_name: type_name.to_string(), serializer, schema: None },
+        }
+    }
+
+    #[test]
+    fn test_abi() {
+        let mut transfer = method_abi("transfer");
+        transfer.is_view = true;
+        transfer.args = vec![
+            arg_abi("receiver_id", "String", SerializerType::JSON),
+            arg_abi("amount", "U128", SerializerType::JSON),
+        ];
+        transfer.result = Some(TypeAbi {
+            type_name: "u64".to_string(),
+            serializer: SerializerType::JSON,
+            schema: None,
+        });
+        let old = ContractAbi::new(vec![transfer, method_abi("get")]);
+
+        let mut transfer = method_abi("transfer");
+        transfer.args = vec![
+            arg_abi("amount", "U64", SerializerType::JSON),
+            arg_abi("receiver_id", "String", SerializerType::JSON),
+            arg_abi("memo", "Option<String>", SerializerType::JSON),
+        ];
+        transfer.result = Some(TypeAbi {
+            type_name: "u64".to_string(),
+            serializer: SerializerType::Borsh,
+            schema: None,
+        });
+        transfer.callbacks = vec![arg_abi("result", "u64", SerializerType::JSON)];
+        let new = ContractAbi::new(vec![transfer]);
+
+        let changes = diff_abi(&old, &new);
+        assert_eq!(
+            changes.iter().map(|change| change.to_string()).collect::<Vec<_>>(),
+            vec![
+                "breaking: `transfer` is no longer a view method",
+                "breaking: `transfer` argument `amount` changed type from `U128` to `U64`",
+                "compatible: `transfer` takes new argument `memo: Option<String>`",
+                "breaking: `transfer` result changed serializer from JSON to Borsh",
+                "breaking: `transfer` callbacks changed",
+                "breaking: `get` is removed",
+            ]
+        );
+        assert!(diff_abi(&new, &new).is_empty());
+
+        // The types with the same schemas are serialized the same way, whatever their names.
+        let schema = Some(serde_json::json!({ "type": "string" }));
+        let mut old = method_abi("a");
+        old.args = vec![arg_abi("amount", "U128", SerializerType::JSON)];
+        old.args[0].ty.schema = schema.clone();
+        let mut new = method_abi("a");
+        new.args = vec![arg_abi("amount", "Balance", SerializerType::JSON)];
+        new.args[0].ty.schema = schema;
+        assert!(diff_abi(&ContractAbi::new(vec![old]), &ContractAbi::new(vec![new])).is_empty());
+    }
+}
@@ -0,0 +1,252 @@
+//! Extraction of the ABI of a contract from its source code, without compiling it to wasm. The
+//! source is parsed with the same visitor that `metadata!{}` uses, so the extracted ABI lists the
+//! same methods as `contract_abi`. The types can not be resolved without compiling the contract,
+//! so they are described only by their names and the schemas are `None`.
+//!
+//! The metadata of two versions of the contract can be compared with `diff::diff` to find the
+//! breaking changes of the interface, and their ABIs with `diff::diff_abi`, which compares the
+//! types by their names.
+//!
+//! The ABI can be turned into an `#[ext_contract]` trait with `client::ext_contract_trait`, to call
+//! the contract with the typed client generated for the trait.
+//!
+//! TypeScript bindings are generated from the metadata with `typescript::typescript_bindings`.
+use near_sdk::abi::{self, ArgAbi, ContractAbi, MethodAbi, TypeAbi};
+use near_sdk_core::{type_name, ArgInfo, BindgenArgType, ImplItemMethodInfo, MetadataVisitor};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use syn::visit::Visit;
+use syn::{Item, Type};
+
+pub mod client;
+pub mod diff;
+pub mod typescript;
+
+/// An error that occurred while reading the source of the contract.
+#[derive(Debug)]
+pub enum Error {
+    /// The source file cannot be read.
+    Io(PathBuf, io::Error),
+    /// The source file is not valid Rust.
+    Parse(PathBuf, syn::Error),
+    /// The contract methods are not valid, e.g. they use unknown serializers.
+    Contract(syn::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(path, err) => write!(f, "Cannot read `{}`: {}", path.display(), err),
+            Error::Parse(path, err) => write!(f, "Cannot parse `{}`: {}", path.display(), err),
+            Error::Contract(err) => write!(f, "Invalid contract: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Extracts the ABI of the contract from the crate at `path`, i.e. from `src/lib.rs` and the files
+/// of the modules it declares. `path` can also be the root file of the contract itself.
+pub fn extract_abi(path: &Path) -> Result<ContractAbi, Error> {
+    let root = if path.is_dir() { path.join("src").join("lib.rs") } else { path.to_path_buf() };
+    let dir = root.parent().unwrap_or_else(|| Path::new("")).to_path_buf();
+    let mut visitor = MetadataVisitor::new();
+    visit_module_file(&mut visitor, &root, &dir)?;
+    let methods = visitor.exported_methods().map_err(Error::Contract)?;
+    Ok(ContractAbi::new(methods.into_iter().map(method_abi).collect()))
+}
+
+/// Visits the source file and the files of the modules it declares, which are looked up in `dir`.
+fn visit_module_file(visitor: &mut MetadataVisitor, path: &Path, dir: &Path) -> Result<(), Error> {
+    let content = fs::read_to_string(path).map_err(|err| Error::Io(path.to_path_buf(), err))?;
+    let file = syn::parse_file(&content).map_err(|err| Error::Parse(path.to_path_buf(), err))?;
+    visitor.visit_file(&file);
+    visit_module_items(visitor, &file.items, dir)
+}
+
+/// Visits the files of the modules declared with `mod name;` among the items.
+fn visit_module_items(
+    visitor: &mut MetadataVisitor,
+    items: &[Item],
+    dir: &Path,
+) -> Result<(), Error> {
+    for item in items {
+        if let Item::Mod(item_mod) = item {
+            let name = item_mod.ident.to_string();
+            match &item_mod.content {
+                // Inline modules are already visited, but they can declare modules in other files.
+                Some((_, items)) => visit_module_items(visitor, items, &dir.join(&name))?,
+                None => {
+                    let file = dir.join(format!("{}.rs", name));
+                    let path = if file.exists() { file } else { dir.join(&name).join("mod.rs") };
+                    visit_module_file(visitor, &path, &dir.join(&name))?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// ABI of the method, without the schemas of the types.
+fn method_abi(method: &ImplItemMethodInfo) -> MethodAbi {
+    let attr_sig_info = &method.attr_signature_info;
+    let arg_abi = |arg: &ArgInfo| ArgAbi {
+        name: arg.ident.to_string(),
+        ty: type_abi(&method.abi_arg_type(arg), arg.serializer_ty),
+    };
+    MethodAbi {
+        name: attr_sig_info.exported_name.to_string(),
+        is_view: attr_sig_info.is_view,
+        is_init: attr_sig_info.is_init,
+        is_payable: attr_sig_info.is_payable,
+        positional_args: method.positional_args,
+        args: attr_sig_info.input_args().map(arg_abi).collect(),
+        callbacks: attr_sig_info
+            .args
+            .iter()
+            .filter(|arg| arg.bindgen_ty == BindgenArgType::CallbackArg)
+            .map(arg_abi)
+            .collect(),
+        callbacks_vec: attr_sig_info
+            .args
+            .iter()
+            .rev()
+            .find(|arg| arg.bindgen_ty == BindgenArgType::CallbackArgVec)
+            .map(arg_abi),
+        result: method.abi_result_type().map(|ty| type_abi(&ty, attr_sig_info.result_serializer)),
+    }
+}
+
+fn type_abi(ty: &Type, serializer: near_sdk_core::SerializerType) -> TypeAbi {
+    TypeAbi { type_name: type_name(ty), serializer: serializer_type(serializer), schema: None }
+}
+
+fn serializer_type(serializer: near_sdk_core::SerializerType) -> abi::SerializerType {
+    match serializer {
+        near_sdk_core::SerializerType::JSON => abi::SerializerType::JSON,
+        near_sdk_core::SerializerType::Borsh => abi::SerializerType::Borsh,
+        near_sdk_core::SerializerType::Raw => abi::SerializerType::Raw,
+        #[cfg(feature = "msgpack")]
+        near_sdk_core::SerializerType::MessagePack => abi::SerializerType::MessagePack,
+        #[cfg(feature = "cbor")]
+        near_sdk_core::SerializerType::CBOR => abi::SerializerType::CBOR,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::abi::SerializerType;
+
+    /// Writes the files into a new temporary directory and returns its path.
+    fn write_crate(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("near-sdk-abi-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        for (path, content) in files {
+            let path = dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_modules() {
+        let dir = write_crate(
+            "modules",
+            &[
+                (
+                    "src/lib.rs",
+                    "mod counter;\nmod admin { mod upgrade; }\nmetadata! { struct A {} }",
+                ),
+                (
+                    "src/counter.rs",
+                    r#"
+                    #[near_bindgen]
+                    impl Counter {
+                        /// Increments the counter.
+                        pub fn increment(&mut self, by: &u64) -> Option<&Self> { }
+                        #[result_serializer(borsh)]
+                        pub fn get(&self) -> u64 { }
+                        fn private(&self) { }
+                    }
+                    "#,
+                ),
+                (
+                    "src/admin/upgrade/mod.rs",
+                    r#"
+                    metadata! {
+                        #[near_bindgen(positional_args = false)]
+                        impl Upgradable for Counter {
+                            #[init]
+                            fn new(#[serializer(borsh)] owner_id: String) -> Self { }
+                            #[payable]
+                            fn on_upgrade(&mut self, #[callback] result: U128, #[callback_vec] all: Vec<U128>) { }
+                        }
+                    }
+                    "#,
+                ),
+            ],
+        );
+        let abi = extract_abi(&dir).unwrap();
+        let names: Vec<_> = abi.methods.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["increment", "get", "new", "on_upgrade"]);
+
+        let increment = &abi.methods[0];
+        assert!(!increment.is_view);
+        assert!(increment.positional_args);
+        assert_eq!(increment.args[0].name, "by");
+        assert_eq!(increment.args[0].ty.type_name, "u64");
+        assert_eq!(increment.result.as_ref().unwrap().type_name, "Option<Counter>");
+        let get = &abi.methods[1];
+        assert!(get.is_view);
+        assert_eq!(get.result.as_ref().unwrap().serializer, SerializerType::Borsh);
+        assert!(get.result.as_ref().unwrap().schema.is_none());
+        let new = &abi.methods[2];
+        assert!(new.is_init);
+        assert!(!new.positional_args);
+        assert_eq!(new.args[0].ty.serializer, SerializerType::Borsh);
+        assert!(new.result.is_none());
+        let on_upgrade = &abi.methods[3];
+        assert!(on_upgrade.is_payable);
+        assert_eq!(on_upgrade.callbacks[0].ty.type_name, "U128");
+        assert_eq!(on_upgrade.callbacks_vec.as_ref().unwrap().ty.type_name, "Vec<U128>");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_missing_module() {
+        let dir = write_crate("missing", &[("src/lib.rs", "mod missing;")]);
+        match extract_abi(&dir) {
+            Err(Error::Io(path, _)) => assert!(path.ends_with("src/missing/mod.rs")),
+            other => panic!("Expected an IO error, got {:?}", other),
+        }
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_duplicate_names() {
+        let dir = write_crate(
+            "duplicate",
+            &[(
+                "src/lib.rs",
+                r#"
+                #[near_bindgen]
+                impl A {
+                    pub fn f(&self) { }
+                    #[near_bindgen(rename = "f")]
+                    pub fn g(&self) { }
+                }
+                "#,
+            )],
+        );
+        match extract_abi(&dir) {
+            Err(Error::Contract(_)) => {}
+            other => panic!("Expected a contract error, got {:?}", other),
+        }
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,132 @@
+//! Writes the ABI of a contract extracted from its source code, or compares the metadata of two
+//! versions of the contract.
+//!
+//! ```text
+//! near-sdk-abi [PATH] [--out FILE]
+//! near-sdk-abi diff [--abi] OLD NEW
+//! near-sdk-abi client ABI [--name NAME]
+//! near-sdk-abi typescript METADATA [--name NAME]
+//! ```
+//! `PATH` is the directory of the contract crate or its root source file, the current directory
+//! by default. The ABI is written as JSON into `FILE` or to the standard output.
+//!
+//! `OLD` and `NEW` are the files with the Borsh-encoded metadata returned by the `metadata` method
+//! of the contracts, or with `--abi` the files with their JSON ABIs, e.g. written by this tool. All
+//! changes are printed, and the exit code is 2 if any of them is breaking.
+//!
+//! `client` prints the `#[ext_contract]` trait named `NAME`, `Contract` by default, for the contract
+//! with the given JSON ABI, e.g. the one written by this tool or returned by `contract_abi`.
+//!
+//! `typescript` prints the TypeScript bindings of the contract with the Borsh-encoded metadata, with
+//! the class of the contract named `NAME`, `Contract` by default.
+use borsh::BorshDeserialize;
+use near_sdk::abi::ContractAbi;
+use near_sdk::Metadata;
+use near_sdk_abi::client::ext_contract_trait;
+use near_sdk_abi::diff::{diff, diff_abi, is_breaking, Change};
+use near_sdk_abi::extract_abi;
+use near_sdk_abi::typescript::typescript_bindings;
+use std::path::{Path, PathBuf};
+use std::process;
+
+const USAGE: &str =
+    "Usage:\n    near-sdk-abi [PATH] [--out FILE]\n    near-sdk-abi diff [--abi] OLD NEW\n    \
+                     near-sdk-abi client ABI [--name NAME]\n    \
+                     near-sdk-abi typescript METADATA [--name NAME]";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(|arg| arg.as_str()) {
+        Some("-h") | Some("--help") => println!("{}", USAGE),
+        Some("diff") => match &args[1..] {
+            [old, new] => {
+                run_diff(diff(&read_metadata(Path::new(old)), &read_metadata(Path::new(new))))
+            }
+            [flag, old, new] if flag == "--abi" => {
+                run_diff(diff_abi(&read_abi(Path::new(old)), &read_abi(Path::new(new))))
+            }
+            _ => exit_with_error(USAGE),
+        },
+        Some("client") => match &args[1..] {
+            [abi] => run_client(Path::new(abi), "Contract"),
+            [abi, flag, name] if flag == "--name" => run_client(Path::new(abi), name),
+            _ => exit_with_error(USAGE),
+        },
+        Some("typescript") => match &args[1..] {
+            [metadata] => run_typescript(Path::new(metadata), "Contract"),
+            [metadata, flag, name] if flag == "--name" => run_typescript(Path::new(metadata), name),
+            _ => exit_with_error(USAGE),
+        },
+        _ => run_extract(args),
+    }
+}
+
+fn run_extract(args: Vec<String>) {
+    let mut path = None;
+    let mut out = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" | "--out" => match args.next() {
+                Some(file) => out = Some(PathBuf::from(file)),
+                None => exit_with_error(USAGE),
+            },
+            _ if path.is_none() => path = Some(PathBuf::from(arg)),
+            _ => exit_with_error(USAGE),
+        }
+    }
+
+    let abi = extract_abi(&path.unwrap_or_else(|| PathBuf::from(".")))
+        .unwrap_or_else(|err| exit_with_error(&err.to_string()));
+    let json = serde_json::to_string_pretty(&abi).expect("Failed to serialize the ABI using JSON");
+    match out {
+        Some(file) => std::fs::write(&file, json).unwrap_or_else(|err| {
+            exit_with_error(&format!("Cannot write `{}`: {}", file.display(), err))
+        }),
+        None => println!("{}", json),
+    }
+}
+
+fn run_diff(changes: Vec<Change>) {
+    for change in &changes {
+        println!("{}", change);
+    }
+    if is_breaking(&changes) {
+        process::exit(2);
+    }
+}
+
+fn run_client(path: &Path, trait_name: &str) {
+    print!("{}", ext_contract_trait(&read_abi(path), trait_name));
+}
+
+fn run_typescript(path: &Path, class_name: &str) {
+    print!("{}", typescript_bindings(&read_metadata(path), class_name));
+}
+
+fn read_abi(path: &Path) -> ContractAbi {
+    let data = std::fs::read(path).unwrap_or_else(|err| {
+        exit_with_error(&format!("Cannot read `{}`: {}", path.display(), err))
+    });
+    serde_json::from_slice(&data).unwrap_or_else(|err| {
+        exit_with_error(&format!("Cannot deserialize the ABI from `{}`: {}", path.display(), err))
+    })
+}
+
+fn read_metadata(path: &Path) -> Metadata {
+    let data = std::fs::read(path).unwrap_or_else(|err| {
+        exit_with_error(&format!("Cannot read `{}`: {}", path.display(), err))
+    });
+    Metadata::try_from_slice(&data).unwrap_or_else(|err| {
+        exit_with_error(&format!(
+            "Cannot deserialize the metadata from `{}`: {}",
+            path.display(),
+            err
+        ))
+    })
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
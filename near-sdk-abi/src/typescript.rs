@@ -0,0 +1,358 @@
+//! Generation of TypeScript bindings from the metadata of the contract: interfaces for the types of
+//! the arguments and the results, and a class with a typed wrapper for each method. The types are
+//! derived from the JSON Schema produced by `near_sdk::abi::json_schema`, so `json_types` such as
+//! `U128` and `U64` are strings, like they are in the JSON passed to the contract.
+//!
+//! The class calls the contract through a `Connection`, that can be implemented with any
+//! JavaScript client of the blockchain. Methods whose arguments or result are not serialized with
+//! JSON, and the methods that only the contract itself can call, are omitted.
+use near_sdk::abi::{json_schema, SerializerType};
+use near_sdk::{AccessRestriction, Metadata, MethodMetadata};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// The interfaces used by the generated class to call the contract.
+const CONNECTION: &str = "\
+export interface CallOptions {
+  gas?: string;
+  attachedDeposit?: string;
+}
+
+export interface Connection {
+  view(contractId: string, methodName: string, args: object): Promise<any>;
+  call(contractId: string, methodName: string, args: object, options?: CallOptions): Promise<any>;
+}
+";
+
+/// Returns the TypeScript module with the bindings of the contract, where the class of the
+/// contract is named `class_name`.
+pub fn typescript_bindings(metadata: &Metadata, class_name: &str) -> String {
+    let mut definitions = BTreeMap::new();
+    let mut interfaces = vec![];
+    let mut wrappers = vec![];
+    for method in &metadata.methods {
+        if let Some((interface, wrapper)) = method_bindings(method, &mut definitions) {
+            interfaces.extend(interface);
+            wrappers.push(wrapper);
+        }
+    }
+    let mut result = format!(
+        "// Generated from the metadata of the contract by near-sdk-abi, do not edit.\n\n{}",
+        CONNECTION
+    );
+    for (name, schema) in &definitions {
+        result.push('\n');
+        result.push_str(&type_definition(name, schema));
+    }
+    for interface in interfaces {
+        result.push('\n');
+        result.push_str(&interface);
+    }
+    result.push_str(&format!(
+        "\nexport class {} {{\n  constructor(readonly contractId: string, readonly connection: \
+         Connection) {{}}\n",
+        class_name
+    ));
+    for wrapper in wrappers {
+        result.push('\n');
+        result.push_str(&wrapper);
+    }
+    result.push_str("}\n");
+    result
+}
+
+/// The interface of the arguments, if any, and the wrapper of the method. `None` if the method
+/// cannot be called with JSON from outside of the contract.
+fn method_bindings(
+    method: &MethodMetadata,
+    definitions: &mut BTreeMap<String, Value>,
+) -> Option<(Option<String>, String)> {
+    if method.access == AccessRestriction::SelfOnly {
+        return None;
+    }
+    let result = match &method.result {
+        // Init methods write the state instead of returning the result.
+        Some(result) if !method.is_init => {
+            if method.result_serializer != SerializerType::JSON {
+                return None;
+            }
+            let schema = json_schema(result, method.result_serializer)?;
+            collect_definitions(&schema, definitions);
+            ts_type(&schema)
+        }
+        _ => "void".to_string(),
+    };
+    let args_name = format!("{}Args", pascal_case(&method.name));
+    let interface = match &method.args {
+        Some(args) => {
+            if method.input_serializer != SerializerType::JSON {
+                return None;
+            }
+            let schema = json_schema(args, method.input_serializer)?;
+            collect_definitions(&schema, definitions);
+            Some(type_definition(&args_name, &schema))
+        }
+        None => None,
+    };
+
+    let mut wrapper = String::new();
+    if let Some(docs) = &method.docs {
+        wrapper.push_str("  /**\n");
+        for line in docs.lines() {
+            wrapper.push_str(&format!("   * {}\n", line).replace(" \n", "\n"));
+        }
+        wrapper.push_str("   */\n");
+    }
+    let (params, args) = match interface {
+        Some(_) => (format!("args: {}", args_name), "args"),
+        None => (String::new(), "{}"),
+    };
+    let name = camel_case(&method.name);
+    if method.is_view {
+        wrapper.push_str(&format!(
+            "  {}({}): Promise<{}> {{\n    return this.connection.view(this.contractId, \"{}\", \
+             {});\n  }}\n",
+            name, params, result, method.name, args
+        ));
+    } else {
+        let params = if params.is_empty() {
+            "options?: CallOptions".to_string()
+        } else {
+            format!("{}, options?: CallOptions", params)
+        };
+        wrapper.push_str(&format!(
+            "  {}({}): Promise<{}> {{\n    return this.connection.call(this.contractId, \"{}\", \
+             {}, options);\n  }}\n",
+            name, params, result, method.name, args
+        ));
+    }
+    Some((interface, wrapper))
+}
+
+/// Moves the referenced definitions of the schema into `definitions`.
+fn collect_definitions(schema: &Value, definitions: &mut BTreeMap<String, Value>) {
+    if let Some(Value::Object(referenced)) = schema.get("definitions") {
+        for (name, schema) in referenced {
+            definitions.insert(ts_name(name), schema.clone());
+        }
+    }
+}
+
+/// `export interface` for the objects with known properties, and `export type` otherwise.
+fn type_definition(name: &str, schema: &Value) -> String {
+    match schema.get("properties") {
+        Some(Value::Object(_)) => format!("export interface {} {}\n", name, ts_type(schema)),
+        _ => format!("export type {} = {};\n", name, ts_type(schema)),
+    }
+}
+
+/// TypeScript type of the values described by the JSON Schema.
+fn ts_type(schema: &Value) -> String {
+    if let Some(Value::String(reference)) = schema.get("$ref") {
+        return ts_name(reference.trim_start_matches("#/definitions/"));
+    }
+    if let Some(constant) = schema.get("const") {
+        return constant.to_string();
+    }
+    if let Some(Value::Array(variants)) = schema.get("anyOf").or_else(|| schema.get("oneOf")) {
+        return variants.iter().map(ts_type).collect::<Vec<_>>().join(" | ");
+    }
+    match schema.get("type").and_then(Value::as_str) {
+        Some("string") => "string".to_string(),
+        Some("integer") | Some("number") => "number".to_string(),
+        Some("boolean") => "boolean".to_string(),
+        Some("null") => "null".to_string(),
+        Some("array") => match schema.get("items") {
+            Some(Value::Array(items)) => {
+                format!("[{}]", items.iter().map(ts_type).collect::<Vec<_>>().join(", "))
+            }
+            Some(items) => {
+                let item = ts_type(items);
+                if item.contains(" | ") {
+                    format!("({})[]", item)
+                } else {
+                    format!("{}[]", item)
+                }
+            }
+            None => "unknown[]".to_string(),
+        },
+        Some("object") => match schema.get("properties") {
+            Some(Value::Object(properties)) => {
+                let required: Vec<&str> = match schema.get("required") {
+                    Some(Value::Array(required)) => {
+                        required.iter().filter_map(Value::as_str).collect()
+                    }
+                    _ => vec![],
+                };
+                let fields: Vec<String> = properties
+                    .iter()
+                    .map(|(name, property)| {
+                        let optional = if required.contains(&name.as_str()) { "" } else { "?" };
+                        let ty = ts_type(property).replace('\n', "\n  ");
+                        format!("  {}{}: {};\n", name, optional, ty)
+                    })
+                    .collect();
+                format!("{{\n{}}}", fields.concat())
+            }
+            _ => match schema.get("additionalProperties") {
+                Some(value @ Value::Object(_)) => format!("Record<string, {}>", ts_type(value)),
+                _ => "object".to_string(),
+            },
+        },
+        _ => "unknown".to_string(),
+    }
+}
+
+/// TypeScript identifier for the Rust type, e.g. `Wrapper_u64` for `Wrapper<u64>`.
+fn ts_name(declaration: &str) -> String {
+    let name: String = declaration
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '_' { c } else { '_' })
+        .collect();
+    name.trim_end_matches('_').to_string()
+}
+
+/// `GetStatus` for `get_status`.
+fn pascal_case(name: &str) -> String {
+    name.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// `getStatus` for `get_status`.
+fn camel_case(name: &str) -> String {
+    let pascal = pascal_case(name);
+    let mut chars = pascal.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use borsh::schema::{BorshSchemaContainer, Definition, Fields};
+    use borsh::BorshSchema;
+    use near_sdk::json_types::U128;
+    use near_sdk::CallbackMetadata;
+    use std::collections::HashMap;
+
+    #[derive(BorshSchema)]
+    #[allow(dead_code)]
+    struct Transfer {
+        receiver_id: String,
+        amount: U128,
+        memo: Option<String>,
+    }
+
+    // The derived schema describes each variant with a struct whose fields are never read.
+    #[allow(dead_code)]
+    mod status {
+        use super::*;
+
+        #[derive(BorshSchema)]
+        pub enum Status {
+            Active,
+            Paused { until: u64 },
+        }
+    }
+    use status::Status;
+
+    fn method(name: &str) -> MethodMetadata {
+        MethodMetadata {
+            name: name.to_string(),
+            docs: None,
+            is_view: false,
+            is_init: false,
+            is_payable: false,
+            access: AccessRestriction::Public,
+            input_serializer: SerializerType::JSON,
+            positional_args: true,
+            args: None,
+            callbacks: vec![],
+            callbacks_vec: None,
+            result_serializer: SerializerType::JSON,
+            result: None,
+        }
+    }
+
+    /// Schema of the `Input` struct with the given fields.
+    fn input(fields: Vec<(&str, BorshSchemaContainer)>) -> Option<BorshSchemaContainer> {
+        let mut definitions = HashMap::new();
+        let mut named_fields = vec![];
+        for (name, container) in fields {
+            named_fields.push((name.to_string(), container.declaration));
+            definitions.extend(container.definitions);
+        }
+        definitions.insert(
+            "Input".to_string(),
+            Definition::Struct { fields: Fields::NamedFields(named_fields) },
+        );
+        Some(BorshSchemaContainer { declaration: "Input".to_string(), definitions })
+    }
+
+    #[test]
+    fn test_bindings() {
+        let mut transfer = method("transfer");
+        transfer.docs =
+            Some("Transfers the tokens.\n\nFails if the balance is too low.".to_string());
+        transfer.is_payable = true;
+        transfer.args = input(vec![
+            ("transfer", Transfer::schema_container()),
+            ("amounts", HashMap::<String, U128>::schema_container()),
+        ]);
+        transfer.callbacks = vec![CallbackMetadata {
+            serializer: SerializerType::Borsh,
+            schema: u64::schema_container(),
+        }];
+        let mut status = method("get_status");
+        status.is_view = true;
+        status.result = Some(Option::<Status>::schema_container());
+        let mut balances = method("balances");
+        balances.is_view = true;
+        balances.result = Some(Vec::<(String, U128)>::schema_container());
+        let mut borsh_method = method("borsh_method");
+        borsh_method.input_serializer = SerializerType::Borsh;
+        borsh_method.args = input(vec![("a", u64::schema_container())]);
+        let mut migrate = method("migrate");
+        migrate.access = AccessRestriction::SelfOnly;
+        let mut new = method("new");
+        new.is_init = true;
+        new.result = Some(Transfer::schema_container());
+        let metadata = Metadata::new(vec![transfer, status, balances, borsh_method, migrate, new]);
+
+        let expected = format!(
+            "// Generated from the metadata of the contract by near-sdk-abi, do not edit.\n\n{}\n\
+             export type Status = \"Active\" | {{\n  Paused: {{\n    until: number;\n  }};\n}};\n\n\
+             export interface Transfer {{\n  receiver_id: string;\n  amount: string;\n  memo?: string | null;\n}}\n\n\
+             export interface TransferArgs {{\n  transfer: Transfer;\n  amounts: Record<string, string>;\n}}\n\n\
+             export class Token {{\n  constructor(readonly contractId: string, readonly connection: Connection) {{}}\n\n  \
+             /**\n   * Transfers the tokens.\n   *\n   * Fails if the balance is too low.\n   */\n  \
+             transfer(args: TransferArgs, options?: CallOptions): Promise<void> {{\n    \
+             return this.connection.call(this.contractId, \"transfer\", args, options);\n  }}\n\n  \
+             getStatus(): Promise<Status | null> {{\n    \
+             return this.connection.view(this.contractId, \"get_status\", {{}});\n  }}\n\n  \
+             balances(): Promise<[string, string][]> {{\n    \
+             return this.connection.view(this.contractId, \"balances\", {{}});\n  }}\n\n  \
+             new(options?: CallOptions): Promise<void> {{\n    \
+             return this.connection.call(this.contractId, \"new\", {{}}, options);\n  }}\n\
+             }}\n",
+            CONNECTION
+        );
+        assert_eq!(typescript_bindings(&metadata, "Token"), expected);
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(ts_name("Wrapper<u64>"), "Wrapper_u64");
+        assert_eq!(pascal_case("ft_balance_of"), "FtBalanceOf");
+        assert_eq!(camel_case("ft_balance_of"), "ftBalanceOf");
+    }
+}
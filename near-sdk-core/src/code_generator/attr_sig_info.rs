@@ -1,7 +1,9 @@
 use syn::export::TokenStream2;
 
-use crate::info_extractor::{ArgInfo, AttrSigInfo, BindgenArgType, SerializerType};
-use quote::quote;
+use crate::code_generator::owned_type::owned_type;
+use crate::info_extractor::{ArgInfo, AttrSigInfo, BindgenArgType, SerializerType, Validator};
+use quote::{format_ident, quote, ToTokens};
+use syn::{GenericArgument, LitByteStr, PathArguments, ReturnType, Type};
 
 impl AttrSigInfo {
     /// Create struct representing input arguments.
@@ -10,13 +12,22 @@ impl AttrSigInfo {
     /// `binding: SUBTYPE,` where `TYPE` is one of the following: `& SUBTYPE`, `&mut SUBTYPE`, `SUBTYPE`,
     /// and `SUBTYPE` is one of the following: `[T; n]`, path like
     /// `std::collections::HashMap<SUBTYPE, SUBTYPE>`, or tuple `(SUBTYPE0, SUBTYPE1, ...)`.
+    /// Borrowed types inside `SUBTYPE` are replaced with owned types, e.g. `&str` with `String`.
+    /// With serde-based serialization `Option` arguments can be omitted from the input, arguments
+    /// with `#[default(expr)]` default to `expr`, and `#[serde(...)]` attributes of the arguments
+    /// are passed to the fields.
     /// # Example
     /// ```ignore
     /// struct Input {
     ///   arg0: Vec<String>,
     ///   arg1: [u64; 10],
     ///   arg2: (u64, Vec<String>),
+    ///   #[serde(default = "default_arg3")]
+    ///   arg3: u64,
+    ///   #[serde(default)]
+    ///   arg4: Option<u64>,
     /// }
+    /// fn default_arg3() -> u64 { 10 }
     /// ```
     pub fn input_struct(&self) -> TokenStream2 {
         let args: Vec<_> = self.input_args().collect();
@@ -24,24 +35,37 @@ impl AttrSigInfo {
             !args.is_empty(),
             "Can only generate input struct for when input args are specified"
         );
-        let attribute = match &self.input_serializer {
-            SerializerType::JSON => quote! {#[derive(serde::Deserialize, serde::Serialize)]},
-            SerializerType::Borsh => {
-                quote! {#[derive(borsh::BorshDeserialize, borsh::BorshSerialize)]}
-            }
-        };
+        let attribute = self.input_serializer.derive_attr();
+        let mut default_fns = TokenStream2::new();
         let mut fields = TokenStream2::new();
         for arg in args {
-            let ArgInfo { ty, ident, .. } = &arg;
+            let ArgInfo { ty, ident, default, serde_attrs, .. } = &arg;
+            let ty = owned_type(ty, None);
+            let mut field_attrs = quote! { #(#serde_attrs)* };
+            if let Some(default) = default {
+                let default_fn = format_ident!("default_{}", ident);
+                let default_fn_str = default_fn.to_string();
+                default_fns.extend(quote! {
+                    fn #default_fn() -> #ty {
+                        #default
+                    }
+                });
+                field_attrs.extend(quote! { #[serde(default = #default_fn_str)] });
+            } else if serde_attrs.is_empty() && self.input_serializer.is_serde() && is_option(&ty) {
+                field_attrs.extend(quote! { #[serde(default)] });
+            }
             fields.extend(quote! {
+                #field_attrs
                 #ident: #ty,
             });
         }
+        // Default functions go after the struct, so that attributes can be prepended to the struct.
         quote! {
             #attribute
             struct Input {
                 #fields
             }
+            #default_fns
         }
     }
 
@@ -121,27 +145,205 @@ impl AttrSigInfo {
         result
     }
 
-    /// Create a sequence of patterns and types to be used in the method signature.
+    /// Create a sequence of arguments of the function that prepares a call of this method on
+    /// another contract. Arguments are taken by value.
     ///
     /// # Example:
     /// ```ignore
-    /// a: u64, b: &mut T, ref mut c: Vec<String>,
+    /// a: u64, b: String, c: Vec<String>,
     /// ```
-    pub fn pat_type_list(&self) -> TokenStream2 {
+    pub fn owned_arg_list(&self) -> TokenStream2 {
         let mut result = TokenStream2::new();
         for arg in self.input_args() {
-            let ArgInfo { original, .. } = &arg;
+            let ArgInfo { ident, ty, .. } = &arg;
+            let ty = owned_type(ty, None);
             result.extend(quote! {
-                #original,
+                #ident: #ty,
             });
         }
         result
     }
 
+    /// The type of the value produced by the call of this method on another contract, with `Self`
+    /// replaced by `self_ty`.
+    pub fn owned_output_type(&self, self_ty: Option<&Type>) -> TokenStream2 {
+        match &self.returns {
+            ReturnType::Default => quote! { () },
+            ReturnType::Type(_, ty) => {
+                let ty = owned_type(ty, self_ty);
+                quote! { #ty }
+            }
+        }
+    }
+
+    /// The type that describes the results of the previous promises that the method reads with
+    /// `#[callback]` or `#[callback_vec]`, with `Self` replaced by `self_ty`, so that the calls of
+    /// the method can only follow the promises that produce them, see `near_sdk::CallbackInput`.
+    pub fn callback_input_type(&self, self_ty: Option<&Type>) -> TokenStream2 {
+        let callback_types = |bindgen_ty: BindgenArgType| {
+            self.args
+                .iter()
+                .filter(move |arg| arg.bindgen_ty == bindgen_ty)
+                .map(move |arg| owned_type(&arg.ty, self_ty))
+        };
+        let callbacks: Vec<Type> = callback_types(BindgenArgType::CallbackArg).collect();
+        match (callbacks.as_slice(), callback_types(BindgenArgType::CallbackArgVec).next_back()) {
+            ([], None) => quote! { near_sdk::NoCallback },
+            ([], Some(ty)) => quote! { near_sdk::CallbackVec<#ty> },
+            ([ty], _) => quote! { near_sdk::Callback<#ty> },
+            (types, _) => quote! { near_sdk::Callback<(#(#types),*)> },
+        }
+    }
+
+    /// Create code that serializes the arguments decomposed with `owned_arg_list` with the given
+    /// serializer and creates the builder of the call of the method with the given name on another
+    /// contract.
+    pub fn function_call_builder(
+        &self,
+        method_name: &LitByteStr,
+        serializer: SerializerType,
+    ) -> TokenStream2 {
+        let args_serialization = self.args_serialization(serializer);
+        quote! {
+            #args_serialization
+            near_sdk::FunctionCallBuilder::new(#method_name.to_vec(), args)
+        }
+    }
+
+    /// Create code that serializes the arguments decomposed with `owned_arg_list` into `args` with
+    /// the given serializer.
+    pub fn args_serialization(&self, serializer: SerializerType) -> TokenStream2 {
+        let has_input_args = self.input_args().next().is_some();
+        let struct_decl;
+        let constructor;
+        let value_ser;
+        if !has_input_args {
+            struct_decl = TokenStream2::new();
+            constructor = TokenStream2::new();
+            value_ser = quote! {let args = vec![]; };
+        } else if serializer == SerializerType::Raw {
+            // Raw input consists of a single argument that is passed as is.
+            let ident = &self.input_args().next().unwrap().ident;
+            struct_decl = TokenStream2::new();
+            constructor = TokenStream2::new();
+            value_ser = quote! {let args = #ident; };
+        } else {
+            struct_decl = self.input_struct();
+            let constructor_call = self.constructor_expr();
+            constructor = quote! {let args = #constructor_call;};
+            let message =
+                format!("Failed to serialize the cross contract args using {}.", serializer.name());
+            let invocation = serializer.serialize_expr(quote! {&args}, &message);
+            value_ser = quote! {
+                let args = #invocation;
+            };
+        }
+        quote! {
+            #struct_decl
+            #constructor
+            #value_ser
+        }
+    }
+
+    /// Create code that checks the arguments decorated with `#[validate(...)]` and panics with
+    /// the message naming the argument if the check fails.
+    /// # Example:
+    /// ```ignore
+    /// if amount < 1 {
+    ///     near_sdk::env::panic(b"Argument `amount` should be at least 1.");
+    /// }
+    /// ```
+    pub fn arg_validation(&self) -> TokenStream2 {
+        let mut result = TokenStream2::new();
+        for arg in &self.args {
+            if arg.validators.is_empty() {
+                continue;
+            }
+            let ident = &arg.ident;
+            // `Option` arguments are checked only when they are present.
+            let is_option = is_option(&arg.ty);
+            // `json_types` integers are compared by their values, e.g. `U128` by its `u128`.
+            let value = if is_json_integer(if is_option { option_arg(&arg.ty) } else { &arg.ty }) {
+                quote! { #ident.0 }
+            } else if is_option {
+                quote! { *#ident }
+            } else {
+                quote! { #ident }
+            };
+            let panic = |message: String| {
+                let message = LitByteStr::new(message.as_bytes(), ident.span());
+                quote! {
+                    near_sdk::env::panic(#message);
+                }
+            };
+            let mut checks = TokenStream2::new();
+            for validator in &arg.validators {
+                match validator {
+                    Validator::AccountId => {
+                        let panic =
+                            panic(format!("Argument `{}` is not a valid account id.", ident));
+                        checks.extend(quote! {
+                            if !near_sdk::env::is_valid_account_id(#ident.as_bytes()) {
+                                #panic
+                            }
+                        });
+                    }
+                    Validator::Range { min, max } => {
+                        if let Some(min) = min {
+                            let panic = panic(format!(
+                                "Argument `{}` should be at least {}.",
+                                ident,
+                                min.to_token_stream()
+                            ));
+                            checks.extend(quote! {
+                                if #value < #min {
+                                    #panic
+                                }
+                            });
+                        }
+                        if let Some(max) = max {
+                            let panic = panic(format!(
+                                "Argument `{}` should be at most {}.",
+                                ident,
+                                max.to_token_stream()
+                            ));
+                            checks.extend(quote! {
+                                if #value > #max {
+                                    #panic
+                                }
+                            });
+                        }
+                    }
+                    Validator::MaxLen(max_len) => {
+                        let panic = panic(format!(
+                            "Argument `{}` should be at most {} long.",
+                            ident,
+                            max_len.to_token_stream()
+                        ));
+                        checks.extend(quote! {
+                            if #ident.len() > #max_len {
+                                #panic
+                            }
+                        });
+                    }
+                }
+            }
+            if is_option {
+                result.extend(quote! {
+                    if let Some(#ident) = &#ident {
+                        #checks
+                    }
+                });
+            } else {
+                result.extend(checks);
+            }
+        }
+        result
+    }
+
     /// Create code that deserializes arguments that were decorated with `#[callback]`
     pub fn callback_deserialization(&self) -> TokenStream2 {
-        self
-            .args
+        self.args
             .iter()
             .filter(|arg| match arg.bindgen_ty {
                 BindgenArgType::CallbackArg => true,
@@ -152,31 +354,33 @@ impl AttrSigInfo {
                 let idx = idx as u64;
                 let ArgInfo { mutability, ident, ty, .. } = arg;
                 let read_data = quote! {
-                let data: Vec<u8> = match near_sdk::env::promise_result(#idx) {
-                    near_sdk::PromiseResult::Successful(x) => x,
-                    _ => panic!("Callback computation {} was not successful", #idx)
-                };
-            };
-                let invocation = match arg.serializer_ty {
-                    SerializerType::JSON => quote! {
-                    serde_json::from_slice(&data).expect("Failed to deserialize callback using JSON")
-                },
-                    SerializerType::Borsh => quote! {
-                    borsh::BorshDeserialize::try_from_slice(&data).expect("Failed to deserialize callback using Borsh")
-                },
+                    let data: Vec<u8> = match near_sdk::env::promise_result(#idx) {
+                        near_sdk::PromiseResult::Successful(x) => x,
+                        _ => panic!("Callback computation {} was not successful", #idx)
+                    };
                 };
+                let message = format!(
+                    "Failed to deserialize callback `{}` of `{}` using {}",
+                    ident,
+                    self.exported_name,
+                    arg.serializer_ty.name()
+                );
+                let invocation = arg.serializer_ty.deserialize_expr(
+                    quote! {&data},
+                    &message,
+                    TokenStream2::new(),
+                );
                 quote! {
-                #acc
-                #read_data
-                let #mutability #ident: #ty = #invocation;
-            }
+                    #acc
+                    #read_data
+                    let #mutability #ident: #ty = #invocation;
+                }
             })
     }
 
     /// Create code that deserializes arguments that were decorated with `#[callback_vec]`.
     pub fn callback_vec_deserialization(&self) -> TokenStream2 {
-        self
-            .args
+        self.args
             .iter()
             .filter(|arg| match arg.bindgen_ty {
                 BindgenArgType::CallbackArgVec => true,
@@ -184,25 +388,62 @@ impl AttrSigInfo {
             })
             .fold(TokenStream2::new(), |acc, arg| {
                 let ArgInfo { mutability, ident, ty, .. } = arg;
-                let invocation = match arg.serializer_ty {
-                    SerializerType::JSON => quote! {
-                    serde_json::from_slice(&data).expect("Failed to deserialize callback using JSON")
-                },
-                    SerializerType::Borsh => quote! {
-                    borsh::BorshDeserialize::try_from_slice(&data).expect("Failed to deserialize callback using Borsh")
-                },
-                };
+                let message = format!(
+                    "Failed to deserialize callback {{}} of `{}` using {}",
+                    self.exported_name,
+                    arg.serializer_ty.name()
+                );
+                let invocation =
+                    arg.serializer_ty.deserialize_expr(quote! {&data}, &message, quote! { i, });
                 quote! {
-                #acc
-                let #mutability #ident: #ty = (0..near_sdk::env::promise_results_count())
-                .map(|i| {
-                    let data: Vec<u8> = match near_sdk::env::promise_result(i) {
-                        near_sdk::PromiseResult::Successful(x) => x,
-                        _ => panic!("Callback computation {} was not successful", i)
-                    };
-                    #invocation
-                }).collect();
-            }
+                    #acc
+                    let #mutability #ident: #ty = (0..near_sdk::env::promise_results_count())
+                    .map(|i| {
+                        let data: Vec<u8> = match near_sdk::env::promise_result(i) {
+                            near_sdk::PromiseResult::Successful(x) => x,
+                            _ => panic!("Callback computation {} was not successful", i)
+                        };
+                        #invocation
+                    }).collect();
+                }
             })
     }
 }
+
+/// Whether the type is `Option<T>`.
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(p) if p.qself.is_none() => {
+            p.path.segments.last().map(|segment| segment.ident == "Option").unwrap_or(false)
+        }
+        _ => false,
+    }
+}
+
+/// `T` for `Option<T>`, and the type itself otherwise.
+fn option_arg(ty: &Type) -> &Type {
+    if let Type::Path(p) = ty {
+        if let Some(PathArguments::AngleBracketed(args)) =
+            p.path.segments.last().map(|segment| &segment.arguments)
+        {
+            if let Some(GenericArgument::Type(ty)) = args.args.first() {
+                return ty;
+            }
+        }
+    }
+    ty
+}
+
+/// Whether the type is one of the integers of `json_types`, e.g. `U128`.
+fn is_json_integer(ty: &Type) -> bool {
+    match ty {
+        Type::Path(p) if p.qself.is_none() => match p.path.segments.last() {
+            Some(segment) => {
+                segment.arguments.is_empty()
+                    && ["U128", "U64", "I128", "I64"].iter().any(|name| segment.ident == name)
+            }
+            None => false,
+        },
+        _ => false,
+    }
+}
@@ -1,4 +1,4 @@
-use crate::info_extractor::{AttrSigInfo, ImplItemMethodInfo, SerializerType};
+use crate::info_extractor::{ArgInfo, AttrSigInfo, ImplItemMethodInfo, SerializerType};
 use quote::quote;
 use syn::export::TokenStream2;
 use syn::ReturnType;
@@ -16,26 +16,60 @@ impl ImplItemMethodInfo {
         let env_creation = quote! {
             near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
         };
+        let view_guard = if attr_signature_info.has_view_attr {
+            // Declared first, so that it is dropped last, after the returned promises are scheduled.
+            quote! {
+                let __near_view_guard = near_sdk::env::ViewGuard::enter();
+            }
+        } else {
+            TokenStream2::new()
+        };
         let arg_struct;
         let arg_parsing;
-        if has_input_args {
-            arg_struct = attr_signature_info.input_struct();
-            let decomposition = attr_signature_info.decomposition_pattern();
-            let serializer_invocation = match attr_signature_info.input_serializer {
-                SerializerType::JSON => quote! {
-                serde_json::from_slice(
-                    &near_sdk::env::input().expect("Expected input since method has arguments.")
-                ).expect("Failed to deserialize input from JSON.")
-                },
-                SerializerType::Borsh => quote! {
-                borsh::BorshDeserialize::try_from_slice(
-                    &near_sdk::env::input().expect("Expected input since method has arguments.")
-                ).expect("Failed to deserialize input from Borsh.")
-                },
-            };
+        if has_input_args && attr_signature_info.input_serializer == SerializerType::Raw {
+            // Raw input is the only input argument, so it is taken without copying.
+            let ArgInfo { mutability, ident, .. } =
+                attr_signature_info.input_args().next().unwrap();
+            arg_struct = TokenStream2::new();
             arg_parsing = quote! {
-                let #decomposition : Input = #serializer_invocation ;
+                let #mutability #ident: Vec<u8> =
+                    near_sdk::env::input().expect("Expected input since method has arguments.");
             };
+        } else if has_input_args {
+            arg_struct = attr_signature_info.input_struct();
+            let decomposition = attr_signature_info.decomposition_pattern();
+            let message = format!(
+                "Failed to deserialize input of `{}` from {}",
+                attr_signature_info.exported_name,
+                attr_signature_info.input_serializer.name()
+            );
+            if !self.positional_args && attr_signature_info.input_serializer == SerializerType::JSON
+            {
+                // Serde accepts structs serialized as arrays, so objects are checked explicitly.
+                let serializer_invocation = attr_signature_info.input_serializer.deserialize_expr(
+                    quote! { &input },
+                    &message,
+                    TokenStream2::new(),
+                );
+                arg_parsing = quote! {
+                    let input = near_sdk::env::input().expect("Expected input since method has arguments.");
+                    if input.iter().find(|b| !b.is_ascii_whitespace()) != Some(&b'{') {
+                        near_sdk::env::panic(b"Expected the arguments as a JSON object.");
+                    }
+                    let #decomposition : Input = #serializer_invocation ;
+                };
+            } else {
+                let serializer_invocation = attr_signature_info.input_serializer.deserialize_expr(
+                    quote! {
+                        &near_sdk::env::input().expect("Expected input since method has arguments.")
+                    },
+                    &message,
+                    TokenStream2::new(),
+                );
+                arg_parsing = quote! {
+                    let #decomposition : Input = #serializer_invocation ;
+                };
+            }
         } else {
             arg_struct = TokenStream2::new();
             arg_parsing = TokenStream2::new();
@@ -43,6 +77,7 @@ impl ImplItemMethodInfo {
 
         let callback_deser = attr_signature_info.callback_deserialization();
         let callback_vec_deser = attr_signature_info.callback_vec_deserialization();
+        let arg_validation = attr_signature_info.arg_validation();
 
         let arg_list = attr_signature_info.arg_list();
         let AttrSigInfo {
@@ -73,9 +108,28 @@ impl ImplItemMethodInfo {
             }
         };
         let body = if *is_init {
+            let migration = match &attr_signature_info.migrate_from {
+                // The old state is read and removed, so that the new state, stored under another
+                // key or another layout, does not leave it behind. The migration runs after the
+                // code deployed by `Upgrade`, whose hash is recorded once the deployment succeeds.
+                Some(old_ty) => {
+                    let ArgInfo { mutability, ident, .. } = &attr_signature_info.args[0];
+                    quote! {
+                        if near_sdk::env::predecessor_account_id() != near_sdk::env::current_account_id() {
+                            near_sdk::env::panic(b"Migration can only be called by the contract itself.");
+                        }
+                        let #mutability #ident: #old_ty = near_sdk::env::state_read()
+                            .unwrap_or_else(|| near_sdk::env::panic(b"The contract has no state to migrate."));
+                        near_sdk::env::state_remove();
+                        near_sdk::upgrade::record_code_hash();
+                    }
+                }
+                None => TokenStream2::new(),
+            };
             quote! {
+                #migration
                 let contract = #struct_type::#ident(#arg_list);
-                near_sdk::env::state_write(&contract);
+                near_sdk::ContractState::state_write(&contract);
             }
         } else {
             let contract_deser;
@@ -84,14 +138,14 @@ impl ImplItemMethodInfo {
             if let Some(receiver) = receiver {
                 let mutability = &receiver.mutability;
                 contract_deser = quote! {
-                    let #mutability contract: #struct_type = near_sdk::env::state_read().unwrap_or_default();
+                    let #mutability contract: #struct_type = near_sdk::ContractState::state_read().unwrap_or_default();
                 };
                 method_invocation = quote! {
                     contract.#ident(#arg_list)
                 };
                 if !is_view {
                     contract_ser = quote! {
-                        near_sdk::env::state_write(&contract);
+                        near_sdk::ContractState::state_write(&contract);
                     };
                 } else {
                     contract_ser = TokenStream2::new();
@@ -110,13 +164,19 @@ impl ImplItemMethodInfo {
                     #contract_ser
                 },
                 ReturnType::Type(_, _) => {
-                    let value_ser = match result_serializer {
-                        SerializerType::JSON => quote! {
-                            let result = serde_json::to_vec(&result).expect("Failed to serialize the return value using JSON.");
-                        },
-                        SerializerType::Borsh => quote! {
-                            let result = borsh::BorshSerialize::try_to_vec(&result).expect("Failed to serialize the return value using Borsh.");
-                        },
+                    let value_ser = if *result_serializer == SerializerType::Raw {
+                        // Raw result is returned as is.
+                        TokenStream2::new()
+                    } else {
+                        let message = format!(
+                            "Failed to serialize the return value using {}.",
+                            result_serializer.name()
+                        );
+                        let invocation =
+                            result_serializer.serialize_expr(quote! {&result}, &message);
+                        quote! {
+                            let result = #invocation;
+                        }
                     };
                     quote! {
                     #contract_deser
@@ -135,20 +195,37 @@ impl ImplItemMethodInfo {
                     #value
                 }
             });
+        let exported_name = &attr_signature_info.exported_name;
+        // Aliases only forward the call to the main wrapper.
+        let alias_wrappers =
+            attr_signature_info.aliases.iter().fold(TokenStream2::new(), |acc, alias| {
+                quote! {
+                    #acc
+                    #non_bindgen_attrs
+                    #[cfg(target_arch = "wasm32")]
+                    #[no_mangle]
+                    pub extern "C" fn #alias() {
+                        #exported_name()
+                    }
+                }
+            });
         quote! {
             #non_bindgen_attrs
             #[cfg(target_arch = "wasm32")]
             #[no_mangle]
-            pub extern "C" fn #ident() {
+            pub extern "C" fn #exported_name() {
                 #panic_hook
                 #env_creation
+                #view_guard
                 #deposit_check
                 #arg_struct
                 #arg_parsing
                 #callback_deser
                 #callback_vec_deser
+                #arg_validation
                 #body
             }
+            #alias_wrappers
         }
     }
 }
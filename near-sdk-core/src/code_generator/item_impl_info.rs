@@ -1,5 +1,9 @@
+use crate::code_generator::item_struct_info::ext_struct_ident;
 use crate::ItemImplInfo;
+use quote::{quote, quote_spanned};
 use syn::export::TokenStream2;
+use syn::spanned::Spanned;
+use syn::{LitByteStr, PathArguments, Type};
 
 impl ItemImplInfo {
     /// Generate the code that wraps
@@ -12,21 +16,71 @@ impl ItemImplInfo {
         }
         res
     }
+
+    /// Generate methods of the struct generated by `ItemStructInfo::ext_self_struct` that prepare
+    /// calls of the contract methods, except initialization methods, on the current account. The
+    /// methods are named after the exported names of the contract methods, which are unique, and
+    /// the struct is found next to the contract struct, e.g. `a::ContractExt` for `impl a::Contract`,
+    /// so the impl should name the contract through the same module path as the struct.
+    pub fn ext_self_code(&self) -> TokenStream2 {
+        let mut ext_path = match &self.ty {
+            Type::Path(type_path) if type_path.qself.is_none() => type_path.path.clone(),
+            _ => return TokenStream2::new(),
+        };
+        match ext_path.segments.last_mut() {
+            Some(segment) => {
+                segment.ident = ext_struct_ident(&segment.ident);
+                segment.arguments = PathArguments::None;
+            }
+            None => return TokenStream2::new(),
+        }
+        let mut methods = TokenStream2::new();
+        for method in &self.methods {
+            let attr_sig_info = &method.attr_signature_info;
+            if !(method.is_public || self.is_trait_impl) || attr_sig_info.is_init {
+                continue;
+            }
+            let non_bindgen_attrs = &attr_sig_info.non_bindgen_attrs;
+            let arg_list = attr_sig_info.owned_arg_list();
+            let output = attr_sig_info.owned_output_type(Some(&self.ty));
+            let input = attr_sig_info.callback_input_type(Some(&self.ty));
+            let exported_name = &attr_sig_info.exported_name;
+            let method_name =
+                LitByteStr::new(exported_name.to_string().as_bytes(), exported_name.span());
+            // The method wrapper reads the arguments with the input serializer.
+            let body =
+                attr_sig_info.function_call_builder(&method_name, attr_sig_info.input_serializer);
+            methods.extend(quote! {
+                #(#non_bindgen_attrs)*
+                pub fn #exported_name(self, #arg_list) -> near_sdk::TypedPromise<#output, #input> {
+                    let call: near_sdk::FunctionCallBuilder<#output, #input> = { #body };
+                    call.with_deposit(self.deposit).with_gas(self.gas).on(&self.account_id)
+                }
+            });
+        }
+        // Errors about the missing struct point at the type of the impl.
+        let ext_ty = quote_spanned! { self.ty.span() => #ext_path };
+        quote! {
+            impl #ext_ty {
+                #methods
+            }
+        }
+    }
 }
 // Rustfmt removes comas.
 #[rustfmt::skip]
 #[cfg(test)]
 mod tests {
-    use syn::{Type, ImplItemMethod, parse_quote};
+    use syn::{Type, ImplItemMethod, ItemImpl, parse_quote};
     use quote::quote;
-    use crate::info_extractor::ImplItemMethodInfo;
+    use crate::info_extractor::{ImplItemMethodInfo, ItemImplInfo, SerializerType};
 
 
     #[test]
     fn trait_implt() {
         let impl_type: Type = syn::parse_str("Hello").unwrap();
         let mut method: ImplItemMethod = syn::parse_str("fn method(&self) { }").unwrap();
-        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).unwrap();
         let actual = method_info.method_wrapper();
         let expected = quote!(
             #[cfg(target_arch = "wasm32")]
@@ -34,7 +88,7 @@ mod tests {
             pub extern "C" fn method() {
                 near_sdk::env::setup_panic_hook();
                 near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
-                let contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                let contract: Hello = near_sdk::ContractState::state_read().unwrap_or_default();
                 contract.method();
             }
         );
@@ -45,7 +99,7 @@ mod tests {
     fn no_args_no_return_no_mut() {
         let impl_type: Type = syn::parse_str("Hello").unwrap();
         let mut method: ImplItemMethod = syn::parse_str("pub fn method(&self) { }").unwrap();
-        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).unwrap();
         let actual = method_info.method_wrapper();
         let expected = quote!(
             #[cfg(target_arch = "wasm32")]
@@ -53,7 +107,7 @@ mod tests {
             pub extern "C" fn method() {
                 near_sdk::env::setup_panic_hook();
                 near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
-                let contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                let contract: Hello = near_sdk::ContractState::state_read().unwrap_or_default();
                 contract.method();
             }
         );
@@ -64,7 +118,7 @@ mod tests {
     fn no_args_no_return_mut() {
         let impl_type: Type = syn::parse_str("Hello").unwrap();
         let mut method: ImplItemMethod = syn::parse_str("pub fn method(&mut self) { }").unwrap();
-        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).unwrap();
         let actual = method_info.method_wrapper();
         let expected = quote!(
             #[cfg(target_arch = "wasm32")]
@@ -75,9 +129,9 @@ mod tests {
                 if near_sdk::env::attached_deposit() != 0 {
                     near_sdk::env::panic(b"Method doesn't accept deposit");
                 }
-                let mut contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                let mut contract: Hello = near_sdk::ContractState::state_read().unwrap_or_default();
                 contract.method();
-                near_sdk::env::state_write(&contract);
+                near_sdk::ContractState::state_write(&contract);
             }
         );
         assert_eq!(expected.to_string(), actual.to_string());
@@ -87,7 +141,7 @@ mod tests {
     fn arg_no_return_no_mut() {
         let impl_type: Type = syn::parse_str("Hello").unwrap();
         let mut method: ImplItemMethod = syn::parse_str("pub fn method(&self, k: u64) { }").unwrap();
-        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).unwrap();
         let actual = method_info.method_wrapper();
         let expected = quote!(
             #[cfg(target_arch = "wasm32")]
@@ -102,8 +156,8 @@ mod tests {
                 let Input { k, }: Input = serde_json::from_slice(
                     &near_sdk::env::input().expect("Expected input since method has arguments.")
                 )
-                .expect("Failed to deserialize input from JSON.");
-                let contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                .unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize input of `method` from JSON: {}", err).as_bytes()));
+                let contract: Hello = near_sdk::ContractState::state_read().unwrap_or_default();
                 contract.method(k, );
             }
         );
@@ -115,7 +169,7 @@ mod tests {
         let impl_type: Type = syn::parse_str("Hello").unwrap();
         let mut method: ImplItemMethod =
             syn::parse_str("pub fn method(&mut self, k: u64, m: Bar) { }").unwrap();
-        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).unwrap();
         let actual = method_info.method_wrapper();
         let expected = quote!(
                 #[cfg(target_arch = "wasm32")]
@@ -134,10 +188,10 @@ mod tests {
                     let Input { k, m, }: Input = serde_json::from_slice(
                         &near_sdk::env::input().expect("Expected input since method has arguments.")
                     )
-                    .expect("Failed to deserialize input from JSON.");
-                    let mut contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                    .unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize input of `method` from JSON: {}", err).as_bytes()));
+                    let mut contract: Hello = near_sdk::ContractState::state_read().unwrap_or_default();
                     contract.method(k, m, );
-                    near_sdk::env::state_write(&contract);
+                    near_sdk::ContractState::state_write(&contract);
                 }
         );
         assert_eq!(expected.to_string(), actual.to_string());
@@ -148,7 +202,7 @@ mod tests {
         let impl_type: Type = syn::parse_str("Hello").unwrap();
         let mut method: ImplItemMethod =
             syn::parse_str("pub fn method(&mut self, k: u64, m: Bar) -> Option<u64> { }").unwrap();
-        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).unwrap();
         let actual = method_info.method_wrapper();
         let expected = quote!(
                 #[cfg(target_arch = "wasm32")]
@@ -167,13 +221,13 @@ mod tests {
                     let Input { k, m, }: Input = serde_json::from_slice(
                         &near_sdk::env::input().expect("Expected input since method has arguments.")
                     )
-                    .expect("Failed to deserialize input from JSON.");
-                    let mut contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                    .unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize input of `method` from JSON: {}", err).as_bytes()));
+                    let mut contract: Hello = near_sdk::ContractState::state_read().unwrap_or_default();
                     let result = contract.method(k, m, );
                     let result =
                         serde_json::to_vec(&result).expect("Failed to serialize the return value using JSON.");
                     near_sdk::env::value_return(&result);
-                    near_sdk::env::state_write(&contract);
+                    near_sdk::ContractState::state_write(&contract);
                 }
         );
         assert_eq!(expected.to_string(), actual.to_string());
@@ -184,7 +238,7 @@ mod tests {
         let impl_type: Type = syn::parse_str("Hello").unwrap();
         let mut method: ImplItemMethod =
             syn::parse_str("pub fn method(&self) -> &Option<u64> { }").unwrap();
-        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).unwrap();
         let actual = method_info.method_wrapper();
         let expected = quote!(
             #[cfg(target_arch = "wasm32")]
@@ -192,7 +246,7 @@ mod tests {
             pub extern "C" fn method() {
                 near_sdk::env::setup_panic_hook();
                 near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
-                let contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                let contract: Hello = near_sdk::ContractState::state_read().unwrap_or_default();
                 let result = contract.method();
                 let result =
                     serde_json::to_vec(&result).expect("Failed to serialize the return value using JSON.");
@@ -206,7 +260,7 @@ mod tests {
     fn arg_ref() {
         let impl_type: Type = syn::parse_str("Hello").unwrap();
         let mut method: ImplItemMethod = syn::parse_str("pub fn method(&self, k: &u64) { }").unwrap();
-        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).unwrap();
         let actual = method_info.method_wrapper();
         let expected = quote!(
                 #[cfg(target_arch = "wasm32")]
@@ -221,8 +275,8 @@ mod tests {
                     let Input { k, }: Input = serde_json::from_slice(
                         &near_sdk::env::input().expect("Expected input since method has arguments.")
                     )
-                    .expect("Failed to deserialize input from JSON.");
-                    let contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                    .unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize input of `method` from JSON: {}", err).as_bytes()));
+                    let contract: Hello = near_sdk::ContractState::state_read().unwrap_or_default();
                     contract.method(&k, );
                 }
         );
@@ -234,7 +288,7 @@ mod tests {
         let impl_type: Type = syn::parse_str("Hello").unwrap();
         let mut method: ImplItemMethod =
             syn::parse_str("pub fn method(&self, k: &mut u64) { }").unwrap();
-        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).unwrap();
         let actual = method_info.method_wrapper();
         let expected = quote!(
             #[cfg(target_arch = "wasm32")]
@@ -249,8 +303,8 @@ mod tests {
                 let Input { mut k, }: Input = serde_json::from_slice(
                     &near_sdk::env::input().expect("Expected input since method has arguments.")
                 )
-                .expect("Failed to deserialize input from JSON.");
-                let contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                .unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize input of `method` from JSON: {}", err).as_bytes()));
+                let contract: Hello = near_sdk::ContractState::state_read().unwrap_or_default();
                 contract.method(&mut k, );
             }
         );
@@ -263,7 +317,7 @@ mod tests {
         let mut method: ImplItemMethod = parse_quote! {
             pub fn method(&self, #[callback] x: &mut u64, y: String, #[callback] z: Vec<u8>) { }
         };
-        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).unwrap();
         let actual = method_info.method_wrapper();
         let expected = quote!(
             #[cfg(target_arch = "wasm32")]
@@ -278,20 +332,20 @@ mod tests {
                 let Input { y, }: Input = serde_json::from_slice(
                     &near_sdk::env::input().expect("Expected input since method has arguments.")
                 )
-                .expect("Failed to deserialize input from JSON.");
+                .unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize input of `method` from JSON: {}", err).as_bytes()));
                 let data: Vec<u8> = match near_sdk::env::promise_result(0u64) {
                     near_sdk::PromiseResult::Successful(x) => x,
                     _ => panic!("Callback computation {} was not successful", 0u64)
                 };
                 let mut x: u64 =
-                    serde_json::from_slice(&data).expect("Failed to deserialize callback using JSON");
+                    serde_json::from_slice(&data).unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize callback `x` of `method` using JSON: {}", err).as_bytes()));
                 let data: Vec<u8> = match near_sdk::env::promise_result(1u64) {
                     near_sdk::PromiseResult::Successful(x) => x,
                     _ => panic!("Callback computation {} was not successful", 1u64)
                 };
                 let z: Vec<u8> =
-                    serde_json::from_slice(&data).expect("Failed to deserialize callback using JSON");
-                let contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                    serde_json::from_slice(&data).unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize callback `z` of `method` using JSON: {}", err).as_bytes()));
+                let contract: Hello = near_sdk::ContractState::state_read().unwrap_or_default();
                 contract.method(&mut x, y, z, );
             }
         );
@@ -304,7 +358,7 @@ mod tests {
         let mut method: ImplItemMethod = parse_quote! {
             pub fn method(&self, #[callback] x: &mut u64, #[callback] y: String) { }
         };
-        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).unwrap();
         let actual = method_info.method_wrapper();
         let expected = quote!(
             #[cfg(target_arch = "wasm32")]
@@ -317,14 +371,14 @@ mod tests {
                     _ => panic!("Callback computation {} was not successful", 0u64)
                 };
                 let mut x: u64 =
-                    serde_json::from_slice(&data).expect("Failed to deserialize callback using JSON");
+                    serde_json::from_slice(&data).unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize callback `x` of `method` using JSON: {}", err).as_bytes()));
                 let data: Vec<u8> = match near_sdk::env::promise_result(1u64) {
                     near_sdk::PromiseResult::Successful(x) => x,
                     _ => panic!("Callback computation {} was not successful", 1u64)
                 };
                 let y: String =
-                    serde_json::from_slice(&data).expect("Failed to deserialize callback using JSON");
-                let contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                    serde_json::from_slice(&data).unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize callback `y` of `method` using JSON: {}", err).as_bytes()));
+                let contract: Hello = near_sdk::ContractState::state_read().unwrap_or_default();
                 contract.method(&mut x, y, );
             }
         );
@@ -338,7 +392,7 @@ mod tests {
         let mut method: ImplItemMethod = parse_quote! {
             pub fn method(&self, #[callback_vec] x: Vec<String>, y: String) { }
         };
-        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).unwrap();
         let actual = method_info.method_wrapper();
         let expected = quote!(
             #[cfg(target_arch = "wasm32")]
@@ -353,17 +407,17 @@ mod tests {
                 let Input { y, }: Input = serde_json::from_slice(
                     &near_sdk::env::input().expect("Expected input since method has arguments.")
                 )
-                .expect("Failed to deserialize input from JSON.");
+                .unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize input of `method` from JSON: {}", err).as_bytes()));
                 let x: Vec<String> = (0..near_sdk::env::promise_results_count())
                     .map(|i| {
                         let data: Vec<u8> = match near_sdk::env::promise_result(i) {
                             near_sdk::PromiseResult::Successful(x) => x,
                             _ => panic!("Callback computation {} was not successful", i)
                         };
-                        serde_json::from_slice(&data).expect("Failed to deserialize callback using JSON")
+                        serde_json::from_slice(&data).unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize callback {} of `method` using JSON: {}", i, err).as_bytes()))
                     })
                     .collect();
-                let contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                let contract: Hello = near_sdk::ContractState::state_read().unwrap_or_default();
                 contract.method(x, y, );
             }
         );
@@ -377,7 +431,7 @@ mod tests {
             #[init]
             pub fn method(k: &mut u64) -> Self { }
         };
-        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).unwrap();
         let actual = method_info.method_wrapper();
         let expected = quote!(
             #[cfg(target_arch = "wasm32")]
@@ -395,9 +449,9 @@ mod tests {
                 let Input { mut k, }: Input = serde_json::from_slice(
                     &near_sdk::env::input().expect("Expected input since method has arguments.")
                 )
-                .expect("Failed to deserialize input from JSON.");
+                .unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize input of `method` from JSON: {}", err).as_bytes()));
                 let contract = Hello::method(&mut k,);
-                near_sdk::env::state_write(&contract);
+                near_sdk::ContractState::state_write(&contract);
             }
         );
         assert_eq!(expected.to_string(), actual.to_string());
@@ -411,7 +465,7 @@ mod tests {
             #[payable]
             pub fn method(k: &mut u64) -> Self { }
         };
-        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).unwrap();
         let actual = method_info.method_wrapper();
         let expected = quote!(
             #[cfg(target_arch = "wasm32")]
@@ -426,9 +480,9 @@ mod tests {
                 let Input { mut k, }: Input = serde_json::from_slice(
                     &near_sdk::env::input().expect("Expected input since method has arguments.")
                 )
-                .expect("Failed to deserialize input from JSON.");
+                .unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize input of `method` from JSON: {}", err).as_bytes()));
                 let contract = Hello::method(&mut k,);
-                near_sdk::env::state_write(&contract);
+                near_sdk::ContractState::state_write(&contract);
             }
         );
         assert_eq!(expected.to_string(), actual.to_string());
@@ -441,7 +495,7 @@ mod tests {
             #[result_serializer(borsh)]
             pub fn method(&mut self, #[serializer(borsh)] k: u64, #[serializer(borsh)]m: Bar) -> Option<u64> { }
         };
-        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).unwrap();
         let actual = method_info.method_wrapper();
         let expected = quote!(
             #[cfg(target_arch = "wasm32")]
@@ -460,13 +514,294 @@ mod tests {
                 let Input { k, m, }: Input = borsh::BorshDeserialize::try_from_slice(
                     &near_sdk::env::input().expect("Expected input since method has arguments.")
                 )
-                .expect("Failed to deserialize input from Borsh.");
-                let mut contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                .unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize input of `method` from Borsh: {}", err).as_bytes()));
+                let mut contract: Hello = near_sdk::ContractState::state_read().unwrap_or_default();
                 let result = contract.method(k, m, );
                 let result = borsh::BorshSerialize::try_to_vec(&result)
                     .expect("Failed to serialize the return value using Borsh.");
                 near_sdk::env::value_return(&result);
-                near_sdk::env::state_write(&contract);
+                near_sdk::ContractState::state_write(&contract);
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn args_return_mut_default_borsh() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            #[result_serializer(json)]
+            pub fn method(&mut self, k: u64, #[callback] #[serializer(json)] m: Bar) -> Option<u64> { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::Borsh, true).unwrap();
+        let actual = method_info.method_wrapper();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn method() {
+                near_sdk::env::setup_panic_hook();
+                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+                if near_sdk::env::attached_deposit() != 0 {
+                    near_sdk::env::panic(b"Method doesn't accept deposit");
+                }
+                #[derive(borsh :: BorshDeserialize, borsh :: BorshSerialize)]
+                struct Input {
+                    k: u64,
+                }
+                let Input { k, }: Input = borsh::BorshDeserialize::try_from_slice(
+                    &near_sdk::env::input().expect("Expected input since method has arguments.")
+                )
+                .unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize input of `method` from Borsh: {}", err).as_bytes()));
+                let data: Vec<u8> = match near_sdk::env::promise_result(0u64) {
+                    near_sdk::PromiseResult::Successful(x) => x,
+                    _ => panic!("Callback computation {} was not successful", 0u64)
+                };
+                let m: Bar =
+                    serde_json::from_slice(&data).unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize callback `m` of `method` using JSON: {}", err).as_bytes()));
+                let mut contract: Hello = near_sdk::ContractState::state_read().unwrap_or_default();
+                let result = contract.method(k, m, );
+                let result = serde_json::to_vec(&result)
+                    .expect("Failed to serialize the return value using JSON.");
+                near_sdk::env::value_return(&result);
+                near_sdk::ContractState::state_write(&contract);
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn raw_arg_return() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            #[result_serializer(raw)]
+            pub fn method(&mut self, #[serializer(raw)] code: &[u8], #[callback] #[serializer(raw)] x: Vec<u8>) -> Vec<u8> { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).unwrap();
+        let actual = method_info.method_wrapper();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn method() {
+                near_sdk::env::setup_panic_hook();
+                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+                if near_sdk::env::attached_deposit() != 0 {
+                    near_sdk::env::panic(b"Method doesn't accept deposit");
+                }
+                let code: Vec<u8> =
+                    near_sdk::env::input().expect("Expected input since method has arguments.");
+                let data: Vec<u8> = match near_sdk::env::promise_result(0u64) {
+                    near_sdk::PromiseResult::Successful(x) => x,
+                    _ => panic!("Callback computation {} was not successful", 0u64)
+                };
+                let x: Vec<u8> = <[u8]>::to_vec(&data);
+                let mut contract: Hello = near_sdk::ContractState::state_read().unwrap_or_default();
+                let result = contract.method(&code, x, );
+                near_sdk::env::value_return(&result);
+                near_sdk::ContractState::state_write(&contract);
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn raw_arg_not_bytes() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            pub fn method(&mut self, #[serializer(raw)] code: String) { }
+        };
+        let err = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Raw serializer can only be used with arguments of type `Vec<u8>` or `&[u8]`."
+        );
+    }
+
+    #[test]
+    fn raw_return_not_bytes() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            #[result_serializer(raw)]
+            pub fn method(&self) -> String { }
+        };
+        let err = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Raw result serializer can only be used with methods returning `Vec<u8>` or `&[u8]`."
+        );
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn args_return_msgpack() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            pub fn method(&self, k: u64) -> Option<u64> { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::MessagePack, true).unwrap();
+        let actual = method_info.method_wrapper();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn method() {
+                near_sdk::env::setup_panic_hook();
+                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+                #[derive(serde :: Deserialize, serde :: Serialize)]
+                struct Input {
+                    k: u64,
+                }
+                let Input { k, }: Input = near_sdk::rmp_serde::from_slice(
+                    &near_sdk::env::input().expect("Expected input since method has arguments.")
+                )
+                .unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize input of `method` from MessagePack: {}", err).as_bytes()));
+                let contract: Hello = near_sdk::ContractState::state_read().unwrap_or_default();
+                let result = contract.method(k, );
+                let result = near_sdk::rmp_serde::to_vec_named(&result)
+                    .expect("Failed to serialize the return value using MessagePack.");
+                near_sdk::env::value_return(&result);
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn args_return_cbor() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            pub fn method(&self, k: u64) -> Option<u64> { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::CBOR, true).unwrap();
+        let actual = method_info.method_wrapper();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn method() {
+                near_sdk::env::setup_panic_hook();
+                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+                #[derive(serde :: Deserialize, serde :: Serialize)]
+                struct Input {
+                    k: u64,
+                }
+                let Input { k, }: Input = near_sdk::serde_cbor::from_slice(
+                    &near_sdk::env::input().expect("Expected input since method has arguments.")
+                )
+                .unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize input of `method` from CBOR: {}", err).as_bytes()));
+                let contract: Hello = near_sdk::ContractState::state_read().unwrap_or_default();
+                let result = contract.method(k, );
+                let result = near_sdk::serde_cbor::to_vec(&result)
+                    .expect("Failed to serialize the return value using CBOR.");
+                near_sdk::env::value_return(&result);
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn default_args() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            pub fn method(&self, k: u64, #[default(10)] limit: u32, #[serde(rename = "from")] from_index: Option<u64>, memo: Option<String>) { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).unwrap();
+        let actual = method_info.method_wrapper();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn method() {
+                near_sdk::env::setup_panic_hook();
+                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+                #[derive(serde :: Deserialize, serde :: Serialize)]
+                struct Input {
+                    k: u64,
+                    #[serde(default = "default_limit")]
+                    limit: u32,
+                    #[serde(rename = "from")]
+                    from_index: Option<u64>,
+                    #[serde(default)]
+                    memo: Option<String>,
+                }
+                fn default_limit() -> u32 {
+                    10
+                }
+                let Input { k, limit, from_index, memo, }: Input = serde_json::from_slice(
+                    &near_sdk::env::input().expect("Expected input since method has arguments.")
+                )
+                .unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize input of `method` from JSON: {}", err).as_bytes()));
+                let contract: Hello = near_sdk::ContractState::state_read().unwrap_or_default();
+                contract.method(k, limit, from_index, memo, );
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn default_arg_borsh() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            pub fn method(&self, #[default(10)] limit: u32) { }
+        };
+        let err = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::Borsh, true).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "`#[default]` and `#[serde]` can only be used with arguments serialized with serde, e.g. JSON."
+        );
+    }
+
+    #[test]
+    fn validated_args() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            pub fn method(&self, #[validate(account_id)] receiver_id: String, #[validate(range(min = 1, max = 100))] amount: U128, #[validate(max_len = 256)] memo: Option<String>, #[validate(range(max = 10))] count: Option<u64>, #[validate(range(min = 1))] fee: Option<U64>) { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).unwrap();
+        let actual = method_info.method_wrapper();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn method() {
+                near_sdk::env::setup_panic_hook();
+                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+                #[derive(serde :: Deserialize, serde :: Serialize)]
+                struct Input {
+                    receiver_id: String,
+                    amount: U128,
+                    #[serde(default)]
+                    memo: Option<String>,
+                    #[serde(default)]
+                    count: Option<u64>,
+                    #[serde(default)]
+                    fee: Option<U64>,
+                }
+                let Input { receiver_id, amount, memo, count, fee, }: Input = serde_json::from_slice(
+                    &near_sdk::env::input().expect("Expected input since method has arguments.")
+                )
+                .unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize input of `method` from JSON: {}", err).as_bytes()));
+                if !near_sdk::env::is_valid_account_id(receiver_id.as_bytes()) {
+                    near_sdk::env::panic(b"Argument `receiver_id` is not a valid account id.");
+                }
+                if amount.0 < 1 {
+                    near_sdk::env::panic(b"Argument `amount` should be at least 1.");
+                }
+                if amount.0 > 100 {
+                    near_sdk::env::panic(b"Argument `amount` should be at most 100.");
+                }
+                if let Some(memo) = &memo {
+                    if memo.len() > 256 {
+                        near_sdk::env::panic(b"Argument `memo` should be at most 256 long.");
+                    }
+                }
+                if let Some(count) = &count {
+                    if *count > 10 {
+                        near_sdk::env::panic(b"Argument `count` should be at most 10.");
+                    }
+                }
+                if let Some(fee) = &fee {
+                    if fee.0 < 1 {
+                        near_sdk::env::panic(b"Argument `fee` should be at least 1.");
+                    }
+                }
+                let contract: Hello = near_sdk::ContractState::state_read().unwrap_or_default();
+                contract.method(receiver_id, amount, memo, count, fee, );
             }
         );
         assert_eq!(expected.to_string(), actual.to_string());
@@ -478,7 +813,7 @@ mod tests {
         let mut method: ImplItemMethod = parse_quote! {
             pub fn method(&self, #[callback] #[serializer(borsh)] x: &mut u64, #[serializer(borsh)] y: String, #[callback] #[serializer(json)] z: Vec<u8>) { }
         };
-        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).unwrap();
         let actual = method_info.method_wrapper();
         let expected = quote!(
             #[cfg(target_arch = "wasm32")]
@@ -493,20 +828,20 @@ mod tests {
                 let Input { y, }: Input = borsh::BorshDeserialize::try_from_slice(
                     &near_sdk::env::input().expect("Expected input since method has arguments.")
                 )
-                .expect("Failed to deserialize input from Borsh.");
+                .unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize input of `method` from Borsh: {}", err).as_bytes()));
                 let data: Vec<u8> = match near_sdk::env::promise_result(0u64) {
                     near_sdk::PromiseResult::Successful(x) => x,
                     _ => panic!("Callback computation {} was not successful", 0u64)
                 };
                 let mut x: u64 = borsh::BorshDeserialize::try_from_slice(&data)
-                    .expect("Failed to deserialize callback using Borsh");
+                    .unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize callback `x` of `method` using Borsh: {}", err).as_bytes()));
                 let data: Vec<u8> = match near_sdk::env::promise_result(1u64) {
                     near_sdk::PromiseResult::Successful(x) => x,
                     _ => panic!("Callback computation {} was not successful", 1u64)
                 };
                 let z: Vec<u8> =
-                    serde_json::from_slice(&data).expect("Failed to deserialize callback using JSON");
-                let contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                    serde_json::from_slice(&data).unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize callback `z` of `method` using JSON: {}", err).as_bytes()));
+                let contract: Hello = near_sdk::ContractState::state_read().unwrap_or_default();
                 contract.method(&mut x, y, z, );
             }
         );
@@ -517,7 +852,49 @@ mod tests {
     fn no_args_no_return_mut_payable() {
         let impl_type: Type = syn::parse_str("Hello").unwrap();
         let mut method: ImplItemMethod = syn::parse_str("#[payable] pub fn method(&mut self) { }").unwrap();
-        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).unwrap();
+        let actual = method_info.method_wrapper();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn method() {
+                near_sdk::env::setup_panic_hook();
+                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+                let mut contract: Hello = near_sdk::ContractState::state_read().unwrap_or_default();
+                contract.method();
+                near_sdk::ContractState::state_write(&contract);
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn view_attr_mut_self() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = syn::parse_str("#[view] pub fn method(&mut self) -> u64 { }").unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).unwrap();
+        let actual = method_info.method_wrapper();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn method() {
+                near_sdk::env::setup_panic_hook();
+                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+                let __near_view_guard = near_sdk::env::ViewGuard::enter();
+                let mut contract: Hello = near_sdk::ContractState::state_read().unwrap_or_default();
+                let result = contract.method();
+                let result = serde_json::to_vec(&result).expect("Failed to serialize the return value using JSON.");
+                near_sdk::env::value_return(&result);
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn call_attr_ref_self() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = syn::parse_str("#[call] pub fn method(&self) { }").unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).unwrap();
         let actual = method_info.method_wrapper();
         let expected = quote!(
             #[cfg(target_arch = "wasm32")]
@@ -525,9 +902,451 @@ mod tests {
             pub extern "C" fn method() {
                 near_sdk::env::setup_panic_hook();
                 near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
-                let mut contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                if near_sdk::env::attached_deposit() != 0 {
+                    near_sdk::env::panic(b"Method doesn't accept deposit");
+                }
+                let contract: Hello = near_sdk::ContractState::state_read().unwrap_or_default();
+                contract.method();
+                near_sdk::ContractState::state_write(&contract);
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn no_view_guard_without_view_attr() {
+        let mut item_impl: ItemImpl = parse_quote! {
+            impl Hello {
+                pub fn get(&self) -> u64 { self.value }
+            }
+        };
+        let info = ItemImplInfo::new(&mut item_impl, &Default::default()).unwrap();
+        let actual = info.methods[0].method_wrapper();
+        assert!(!actual.to_string().contains("ViewGuard"));
+        let expected = quote!(
+            impl Hello {
+                pub fn get(&self) -> u64 { self.value }
+            }
+        );
+        assert_eq!(expected.to_string(), quote!(#item_impl).to_string());
+    }
+
+    #[test]
+    fn view_guard_in_body() {
+        let mut item_impl: ItemImpl = parse_quote! {
+            impl Hello {
+                #[view]
+                pub fn get(&mut self) -> u64 { self.value }
+            }
+        };
+        ItemImplInfo::new(&mut item_impl, &Default::default()).unwrap();
+        let expected = quote!(
+            impl Hello {
+                pub fn get(&mut self) -> u64 {
+                    #[cfg(any(debug_assertions, not(target_arch = "wasm32")))]
+                    let __near_view_guard = near_sdk::env::ViewGuard::enter();
+                    self.value
+                }
+            }
+        );
+        assert_eq!(expected.to_string(), quote!(#item_impl).to_string());
+    }
+
+    #[test]
+    fn view_attr_on_init() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = syn::parse_str("#[init] #[view] pub fn new() -> Self { }").unwrap();
+        let err = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).err().unwrap();
+        assert_eq!(err.to_string(), "Init method must be mutable (not view)");
+    }
+
+    #[test]
+    fn migrate() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            #[migrate]
+            pub fn migrate(old: OldHello, owner: String) -> Self { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).unwrap();
+        let actual = method_info.method_wrapper();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn migrate() {
+                near_sdk::env::setup_panic_hook();
+                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+                if near_sdk::env::attached_deposit() != 0 {
+                    near_sdk::env::panic(b"Method doesn't accept deposit");
+                }
+                #[derive(serde :: Deserialize, serde :: Serialize)]
+                struct Input {
+                    owner: String,
+                }
+                let Input { owner, }: Input = serde_json::from_slice(
+                    &near_sdk::env::input().expect("Expected input since method has arguments.")
+                )
+                .unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize input of `migrate` from JSON: {}", err).as_bytes()));
+                if near_sdk::env::predecessor_account_id() != near_sdk::env::current_account_id() {
+                    near_sdk::env::panic(b"Migration can only be called by the contract itself.");
+                }
+                let old: OldHello = near_sdk::env::state_read()
+                    .unwrap_or_else(|| near_sdk::env::panic(b"The contract has no state to migrate."));
+                near_sdk::env::state_remove();
+                near_sdk::upgrade::record_code_hash();
+                let contract = Hello::migrate(old, owner,);
+                near_sdk::ContractState::state_write(&contract);
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn init_migrate_from() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            #[init(migrate_from = v1::Hello)]
+            pub fn migrate(old: &mut v1::Hello) -> Self { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).unwrap();
+        let actual = method_info.method_wrapper();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn migrate() {
+                near_sdk::env::setup_panic_hook();
+                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+                if near_sdk::env::attached_deposit() != 0 {
+                    near_sdk::env::panic(b"Method doesn't accept deposit");
+                }
+                if near_sdk::env::predecessor_account_id() != near_sdk::env::current_account_id() {
+                    near_sdk::env::panic(b"Migration can only be called by the contract itself.");
+                }
+                let mut old: v1::Hello = near_sdk::env::state_read()
+                    .unwrap_or_else(|| near_sdk::env::panic(b"The contract has no state to migrate."));
+                near_sdk::env::state_remove();
+                near_sdk::upgrade::record_code_hash();
+                let contract = Hello::migrate(&mut old,);
+                near_sdk::ContractState::state_write(&contract);
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn migrate_errors() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! { #[migrate] pub fn migrate() -> Self { } };
+        let err = ImplItemMethodInfo::new(&mut method, impl_type.clone(), SerializerType::JSON, true).err().unwrap();
+        assert_eq!(err.to_string(), "Migration method should take the old state as the first argument.");
+
+        let mut method: ImplItemMethod = parse_quote! {
+            #[migrate] pub fn migrate(#[callback] old: OldHello) -> Self { }
+        };
+        let err = ImplItemMethodInfo::new(&mut method, impl_type.clone(), SerializerType::JSON, true).err().unwrap();
+        assert_eq!(err.to_string(), "Migration method should take the old state as the first argument.");
+
+        let mut method: ImplItemMethod = parse_quote! {
+            #[init(migrate_from = OldHello)] pub fn migrate(&mut self, old: OldHello) { }
+        };
+        let err = ImplItemMethodInfo::new(&mut method, impl_type.clone(), SerializerType::JSON, true).err().unwrap();
+        assert_eq!(err.to_string(), "Migration method cannot take `self`.");
+
+        let mut method: ImplItemMethod = parse_quote! {
+            #[migrate] #[init(migrate_from = OldHello)] pub fn migrate(old: OldHello) -> Self { }
+        };
+        let err = ImplItemMethodInfo::new(&mut method, impl_type.clone(), SerializerType::JSON, true).err().unwrap();
+        assert_eq!(err.to_string(), "Use either `#[migrate]` or `#[init(migrate_from = ...)]`, not both.");
+
+        let mut method: ImplItemMethod = parse_quote! {
+            #[migrate] #[view] pub fn migrate(old: OldHello) -> Self { }
+        };
+        let err = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).err().unwrap();
+        assert_eq!(err.to_string(), "Init method must be mutable (not view)");
+    }
+
+    #[test]
+    fn rename_and_alias() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            #[near_bindgen(rename = "ft_total_supply")]
+            #[alias("total_supply", "get_total_supply")]
+            pub fn method(&self) { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).unwrap();
+        let actual = method_info.method_wrapper();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn ft_total_supply() {
+                near_sdk::env::setup_panic_hook();
+                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+                let contract: Hello = near_sdk::ContractState::state_read().unwrap_or_default();
                 contract.method();
-                near_sdk::env::state_write(&contract);
+            }
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn total_supply() {
+                ft_total_supply()
+            }
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn get_total_supply() {
+                ft_total_supply()
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+        assert!(method.attrs.is_empty());
+    }
+
+    #[test]
+    fn invalid_alias() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            #[alias("total supply")]
+            pub fn method(&self) { }
+        };
+        let err = ImplItemMethodInfo::new(&mut method, impl_type, SerializerType::JSON, true).err().unwrap();
+        assert_eq!(err.to_string(), "`total supply` is not a valid method name.");
+    }
+
+    #[test]
+    fn duplicate_exported_names() {
+        let mut item_impl: ItemImpl = parse_quote! {
+            impl Hello {
+                pub fn total_supply(&self) { }
+                #[alias("total_supply")]
+                pub fn ft_total_supply(&self) { }
+            }
+        };
+        let err = ItemImplInfo::new(&mut item_impl, &Default::default()).err().unwrap();
+        assert_eq!(err.to_string(), "Method `total_supply` is exported more than once.");
+
+        // Private methods are not exported.
+        let mut item_impl: ItemImpl = parse_quote! {
+            impl Hello {
+                fn total_supply(&self) { }
+                #[near_bindgen(rename = "total_supply")]
+                pub fn ft_total_supply(&self) { }
+            }
+        };
+        assert!(ItemImplInfo::new(&mut item_impl, &Default::default()).is_ok());
+
+        // Methods compiled under mutually exclusive conditions.
+        let mut item_impl: ItemImpl = parse_quote! {
+            impl Hello {
+                #[cfg(feature = "v2")]
+                pub fn total_supply(&self) { }
+                #[cfg(all(test, not(feature = "v2")))]
+                pub fn total_supply(&self) { }
+            }
+        };
+        assert!(ItemImplInfo::new(&mut item_impl, &Default::default()).is_ok());
+
+        // A method compiled unconditionally conflicts with any conditional one.
+        let mut item_impl: ItemImpl = parse_quote! {
+            impl Hello {
+                #[cfg(feature = "v2")]
+                pub fn total_supply(&self) { }
+                #[cfg(not(feature = "v2"))]
+                pub fn total_supply(&self) { }
+                pub fn total_supply(&self) { }
+            }
+        };
+        let err = ItemImplInfo::new(&mut item_impl, &Default::default()).err().unwrap();
+        assert_eq!(err.to_string(), "Method `total_supply` is exported more than once.");
+
+        // Different conditions can hold together.
+        let mut item_impl: ItemImpl = parse_quote! {
+            impl Hello {
+                #[cfg(feature = "v2")]
+                pub fn total_supply(&self) { }
+                #[cfg(feature = "v3")]
+                pub fn total_supply(&self) { }
+            }
+        };
+        let err = ItemImplInfo::new(&mut item_impl, &Default::default()).err().unwrap();
+        assert_eq!(err.to_string(), "Method `total_supply` is exported more than once.");
+    }
+
+    #[test]
+    fn ext_self_methods() {
+        let mut item_impl: ItemImpl = parse_quote! {
+            impl Hello {
+                /// Merges the values.
+                pub fn merge(&self, #[callback] x: u64, y: &str, z: &[u64]) -> &Self { }
+                #[init]
+                pub fn new() -> Self { }
+                fn internal(&self) { }
+            }
+        };
+        let info = ItemImplInfo::new(&mut item_impl, &Default::default()).unwrap();
+        let actual = info.ext_self_code();
+        let expected = quote!(
+            impl HelloExt {
+                #[doc = r" Merges the values."]
+                pub fn merge(self, y: String, z: Vec<u64>,) -> near_sdk::TypedPromise<Hello, near_sdk::Callback<u64> > {
+                    let call: near_sdk::FunctionCallBuilder<Hello, near_sdk::Callback<u64> > = {
+                        #[derive(serde :: Deserialize, serde :: Serialize)]
+                        struct Input {
+                            y: String,
+                            z: Vec<u64>,
+                        }
+                        let args = Input { y, z, };
+                        let args = serde_json::to_vec(&args)
+                            .expect("Failed to serialize the cross contract args using JSON.");
+                        near_sdk::FunctionCallBuilder::new(b"merge".to_vec(), args)
+                    };
+                    call.with_deposit(self.deposit).with_gas(self.gas).on(&self.account_id)
+                }
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn ext_self_methods_module_path() {
+        let mut item_impl: ItemImpl = parse_quote! {
+            impl contract::Hello<u64> {
+                #[near_bindgen(rename = "hello_transfer")]
+                pub fn transfer(&mut self) { }
+            }
+        };
+        let info = ItemImplInfo::new(&mut item_impl, &Default::default()).unwrap();
+        let actual = info.ext_self_code();
+        let expected = quote!(
+            impl contract::HelloExt {
+                pub fn hello_transfer(self, ) -> near_sdk::TypedPromise<(), near_sdk::NoCallback> {
+                    let call: near_sdk::FunctionCallBuilder<(), near_sdk::NoCallback> = {
+                        let args = vec![];
+                        near_sdk::FunctionCallBuilder::new(b"hello_transfer".to_vec(), args)
+                    };
+                    call.with_deposit(self.deposit).with_gas(self.gas).on(&self.account_id)
+                }
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn instantiate_generic_impl() {
+        let mut item_impl: ItemImpl = parse_quote! {
+            impl<'a, T: Policy> Token<'a, T> {
+                pub fn get_policy(&self, key: Option<T>) -> T { }
+            }
+        };
+        let args = syn::parse_str(r#"instantiate = "Token<'static, DefaultPolicy>""#).unwrap();
+        let info = ItemImplInfo::new(&mut item_impl, &args).unwrap();
+        let actual = info.wrapper_code();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn get_policy() {
+                near_sdk::env::setup_panic_hook();
+                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+                #[derive(serde :: Deserialize, serde :: Serialize)]
+                struct Input {
+                    #[serde(default)]
+                    key: Option<DefaultPolicy>,
+                }
+                let Input { key, }: Input = serde_json::from_slice(
+                    &near_sdk::env::input().expect("Expected input since method has arguments.")
+                )
+                .unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize input of `get_policy` from JSON: {}", err).as_bytes()));
+                let contract: Token::<'static, DefaultPolicy> = near_sdk::ContractState::state_read().unwrap_or_default();
+                let result = contract.get_policy(key, );
+                let result = serde_json::to_vec(&result)
+                    .expect("Failed to serialize the return value using JSON.");
+                near_sdk::env::value_return(&result);
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn instantiate_mismatch() {
+        let mut item_impl: ItemImpl = parse_quote! {
+            impl<T, U> Token<T, U> { }
+        };
+        let args = syn::parse_str(r#"instantiate = "Token<u64>""#).unwrap();
+        let err = ItemImplInfo::new(&mut item_impl, &args).err().unwrap();
+        assert_eq!(err.to_string(), "`instantiate` type should be an instantiation of `Token<T,U>`.");
+
+        let mut item_impl: ItemImpl = parse_quote! {
+            impl<T, U> Token<T, Vec<U> > { }
+        };
+        let args = syn::parse_str(r#"instantiate = "Token<u64, Vec<u64> >""#).unwrap();
+        let err = ItemImplInfo::new(&mut item_impl, &args).err().unwrap();
+        assert_eq!(err.to_string(), "Type parameter `U` is not determined by the `instantiate` type.");
+    }
+
+    #[test]
+    fn no_positional_args() {
+        let mut item_impl: ItemImpl = parse_quote! {
+            impl Hello {
+                pub fn method(&self, k: u64) { }
+            }
+        };
+        let args = syn::parse_str("positional_args = false").unwrap();
+        let info = ItemImplInfo::new(&mut item_impl, &args).unwrap();
+        let actual = info.wrapper_code();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn method() {
+                near_sdk::env::setup_panic_hook();
+                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+                #[derive(serde :: Deserialize, serde :: Serialize)]
+                struct Input {
+                    k: u64,
+                }
+                let input = near_sdk::env::input().expect("Expected input since method has arguments.");
+                if input.iter().find(|b| !b.is_ascii_whitespace()) != Some(&b'{') {
+                    near_sdk::env::panic(b"Expected the arguments as a JSON object.");
+                }
+                let Input { k, }: Input = serde_json::from_slice(&input)
+                    .unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize input of `method` from JSON: {}", err).as_bytes()));
+                let contract: Hello = near_sdk::ContractState::state_read().unwrap_or_default();
+                contract.method(k, );
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn no_positional_args_default() {
+        let mut item_impl: ItemImpl = parse_quote! {
+            impl Hello {
+                pub fn method(&self, k: u64, #[default(5)] fee: u64, memo: Option<String>) { }
+            }
+        };
+        let args = syn::parse_str("positional_args = false").unwrap();
+        let info = ItemImplInfo::new(&mut item_impl, &args).unwrap();
+        let actual = info.wrapper_code();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn method() {
+                near_sdk::env::setup_panic_hook();
+                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+                #[derive(serde :: Deserialize, serde :: Serialize)]
+                struct Input {
+                    k: u64,
+                    #[serde(default = "default_fee")]
+                    fee: u64,
+                    #[serde(default)]
+                    memo: Option<String>,
+                }
+                fn default_fee() -> u64 {
+                    5
+                }
+                let input = near_sdk::env::input().expect("Expected input since method has arguments.");
+                if input.iter().find(|b| !b.is_ascii_whitespace()) != Some(&b'{') {
+                    near_sdk::env::panic(b"Expected the arguments as a JSON object.");
+                }
+                let Input { k, fee, memo, }: Input = serde_json::from_slice(&input)
+                    .unwrap_or_else(|err| near_sdk::env::panic(format!("Failed to deserialize input of `method` from JSON: {}", err).as_bytes()));
+                let contract: Hello = near_sdk::ContractState::state_read().unwrap_or_default();
+                contract.method(k, fee, memo, );
             }
         );
         assert_eq!(expected.to_string(), actual.to_string());
@@ -0,0 +1,336 @@
+use crate::info_extractor::ItemStructInfo;
+use quote::{format_ident, quote};
+use syn::export::TokenStream2;
+use syn::{parse_quote, Ident, LitByteStr, Member};
+
+/// The name of the struct that prepares calls of the methods of the given contract. It has the
+/// span of the contract name, so that a conflict with another type points at the contract.
+pub(crate) fn ext_struct_ident(contract_ident: &Ident) -> Ident {
+    format_ident!("{}Ext", contract_ident, span = contract_ident.span())
+}
+
+impl ItemStructInfo {
+    /// Generate the struct named `<Contract>Ext` that prepares calls of the methods of this
+    /// contract on the current account, e.g. callbacks. The methods themselves are generated for
+    /// each `impl` section, possibly in another module, so the fields are visible in the crate.
+    pub fn ext_self_struct(&self) -> TokenStream2 {
+        let ItemStructInfo { ident, vis, generics, .. } = self;
+        let ext_ident = ext_struct_ident(ident);
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        quote! {
+            /// Prepares calls of the methods of the contract on the current account.
+            #vis struct #ext_ident {
+                pub(crate) account_id: near_sdk::AccountId,
+                pub(crate) deposit: near_sdk::Balance,
+                pub(crate) gas: near_sdk::Gas,
+            }
+
+            impl #ext_ident {
+                /// Attach the given amount of tokens to the call.
+                pub fn with_deposit(mut self, deposit: near_sdk::Balance) -> Self {
+                    self.deposit = deposit;
+                    self
+                }
+
+                /// Attach the given amount of gas to the call.
+                pub fn with_gas(mut self, gas: near_sdk::Gas) -> Self {
+                    self.gas = gas;
+                    self
+                }
+            }
+
+            impl #impl_generics #ident #ty_generics #where_clause {
+                /// Prepare a call of a method of this contract on the current account.
+                pub fn ext_self() -> #ext_ident {
+                    #ext_ident {
+                        account_id: near_sdk::env::current_account_id(),
+                        deposit: 0,
+                        gas: near_sdk::DEFAULT_FUNCTION_CALL_GAS,
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl ItemStructInfo {
+    /// Generate the implementation of `near_sdk::ContractState` that the method wrappers use to
+    /// read and write the state. By default the struct is stored with Borsh under `STATE` key, or
+    /// under the key set with `state_key`, and with `lazy_state` each field is stored under
+    /// `STATE.<field>` key. With `state_version` the version is checked when an existing state is
+    /// read and is stored when the state is written.
+    pub fn contract_state_impl(&self) -> TokenStream2 {
+        let ItemStructInfo { ident, generics, lazy_fields, state_key, state_version, .. } = self;
+        let (state_read, state_write) = match (lazy_fields, state_key) {
+            (None, None) => (
+                quote! { near_sdk::env::state_read() },
+                quote! { near_sdk::env::state_write(self); },
+            ),
+            (None, Some(state_key)) => {
+                let key = LitByteStr::new(state_key.as_bytes(), ident.span());
+                (
+                    quote! { near_sdk::env::state_read_at(#key) },
+                    quote! { near_sdk::env::state_write_at(#key, self); },
+                )
+            }
+            (Some(fields), _) => {
+                let state_key = state_key.as_deref().unwrap_or("STATE");
+                let keys: Vec<LitByteStr> = fields
+                    .iter()
+                    .map(|member| {
+                        let name = match member {
+                            Member::Named(ident) => ident.to_string(),
+                            Member::Unnamed(index) => index.index.to_string(),
+                        };
+                        LitByteStr::new(format!("{}.{}", state_key, name).as_bytes(), ident.span())
+                    })
+                    .collect();
+                let first_key = &keys[0];
+                (
+                    quote! {
+                        if !near_sdk::env::storage_has_key(#first_key) {
+                            return None;
+                        }
+                        Some(Self {
+                            #(#fields: near_sdk::collections::Lazy::from_key(#keys.to_vec()),)*
+                        })
+                    },
+                    quote! {
+                        #(self.#fields.flush(#keys);)*
+                    },
+                )
+            }
+        };
+        let (state_read, state_write) = match state_version {
+            None => (state_read, state_write),
+            Some(version) => (
+                quote! {
+                    let state: Option<Self> = { #state_read };
+                    if state.is_some() {
+                        near_sdk::env::assert_state_version(#version);
+                    }
+                    state
+                },
+                quote! {
+                    #state_write
+                    if near_sdk::env::state_version() != #version {
+                        near_sdk::env::set_state_version(#version);
+                    }
+                },
+            ),
+        };
+        let mut generics = generics.clone();
+        if lazy_fields.is_none() {
+            generics
+                .make_where_clause()
+                .predicates
+                .push(parse_quote! { Self: borsh::BorshDeserialize + borsh::BorshSerialize });
+        }
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        quote! {
+            impl #impl_generics near_sdk::ContractState for #ident #ty_generics #where_clause {
+                fn state_read() -> Option<Self> {
+                    #state_read
+                }
+
+                fn state_write(&self) {
+                    #state_write
+                }
+            }
+        }
+    }
+}
+
+// Rustfmt removes comas.
+#[rustfmt::skip]
+#[cfg(test)]
+mod tests {
+    use syn::{parse_quote, ItemStruct};
+    use quote::quote;
+    use crate::info_extractor::{BindgenArgs, ItemStructInfo};
+
+    #[test]
+    fn ext_self_struct() {
+        let mut s: ItemStruct = syn::parse_str("pub struct Hello { value: u64 }").unwrap();
+        let info = ItemStructInfo::new(&mut s, &Default::default()).unwrap();
+        let actual = info.ext_self_struct();
+        let expected = quote!(
+            /// Prepares calls of the methods of the contract on the current account.
+            pub struct HelloExt {
+                pub(crate) account_id: near_sdk::AccountId,
+                pub(crate) deposit: near_sdk::Balance,
+                pub(crate) gas: near_sdk::Gas,
+            }
+
+            impl HelloExt {
+                /// Attach the given amount of tokens to the call.
+                pub fn with_deposit(mut self, deposit: near_sdk::Balance) -> Self {
+                    self.deposit = deposit;
+                    self
+                }
+
+                /// Attach the given amount of gas to the call.
+                pub fn with_gas(mut self, gas: near_sdk::Gas) -> Self {
+                    self.gas = gas;
+                    self
+                }
+            }
+
+            impl Hello {
+                /// Prepare a call of a method of this contract on the current account.
+                pub fn ext_self() -> HelloExt {
+                    HelloExt {
+                        account_id: near_sdk::env::current_account_id(),
+                        deposit: 0,
+                        gas: near_sdk::DEFAULT_FUNCTION_CALL_GAS,
+                    }
+                }
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn contract_state() {
+        let mut s: ItemStruct = syn::parse_str("pub struct Hello<T> where T: Clone { value: T }").unwrap();
+        let info = ItemStructInfo::new(&mut s, &Default::default()).unwrap();
+        let actual = info.contract_state_impl();
+        let expected = quote!(
+            impl<T> near_sdk::ContractState for Hello<T>
+            where
+                T: Clone,
+                Self: borsh::BorshDeserialize + borsh::BorshSerialize
+            {
+                fn state_read() -> Option<Self> {
+                    near_sdk::env::state_read()
+                }
+
+                fn state_write(&self) {
+                    near_sdk::env::state_write(self);
+                }
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn lazy_contract_state() {
+        let mut s: ItemStruct = parse_quote! {
+            pub struct Hello { value: Lazy<u64>, history: Lazy<Vec<u64>> }
+        };
+        let args: BindgenArgs = parse_quote! { lazy_state };
+        let info = ItemStructInfo::new(&mut s, &args).unwrap();
+        let actual = info.contract_state_impl();
+        let expected = quote!(
+            impl near_sdk::ContractState for Hello {
+                fn state_read() -> Option<Self> {
+                    if !near_sdk::env::storage_has_key(b"STATE.value") {
+                        return None;
+                    }
+                    Some(Self {
+                        value: near_sdk::collections::Lazy::from_key(b"STATE.value".to_vec()),
+                        history: near_sdk::collections::Lazy::from_key(b"STATE.history".to_vec()),
+                    })
+                }
+
+                fn state_write(&self) {
+                    self.value.flush(b"STATE.value");
+                    self.history.flush(b"STATE.history");
+                }
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn state_key() {
+        let mut s: ItemStruct = parse_quote! { pub struct Hello { value: u64 } };
+        let args: BindgenArgs = parse_quote! { state_key = "CONFIG" };
+        let info = ItemStructInfo::new(&mut s, &args).unwrap();
+        let actual = info.contract_state_impl();
+        let expected = quote!(
+            impl near_sdk::ContractState for Hello
+            where
+                Self: borsh::BorshDeserialize + borsh::BorshSerialize
+            {
+                fn state_read() -> Option<Self> {
+                    near_sdk::env::state_read_at(b"CONFIG")
+                }
+
+                fn state_write(&self) {
+                    near_sdk::env::state_write_at(b"CONFIG", self);
+                }
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+
+        let mut s: ItemStruct = parse_quote! { pub struct Hello { value: Lazy<u64> } };
+        let args: BindgenArgs = parse_quote! { state_key = "CONFIG", lazy_state };
+        let info = ItemStructInfo::new(&mut s, &args).unwrap();
+        let actual = info.contract_state_impl();
+        let expected = quote!(
+            impl near_sdk::ContractState for Hello {
+                fn state_read() -> Option<Self> {
+                    if !near_sdk::env::storage_has_key(b"CONFIG.value") {
+                        return None;
+                    }
+                    Some(Self {
+                        value: near_sdk::collections::Lazy::from_key(b"CONFIG.value".to_vec()),
+                    })
+                }
+
+                fn state_write(&self) {
+                    self.value.flush(b"CONFIG.value");
+                }
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn state_version() {
+        let mut s: ItemStruct = parse_quote! { pub struct Hello { value: u64 } };
+        let args: BindgenArgs = parse_quote! { state_version = 2 };
+        let info = ItemStructInfo::new(&mut s, &args).unwrap();
+        let actual = info.contract_state_impl();
+        let expected = quote!(
+            impl near_sdk::ContractState for Hello
+            where
+                Self: borsh::BorshDeserialize + borsh::BorshSerialize
+            {
+                fn state_read() -> Option<Self> {
+                    let state: Option<Self> = { near_sdk::env::state_read() };
+                    if state.is_some() {
+                        near_sdk::env::assert_state_version(2u32);
+                    }
+                    state
+                }
+
+                fn state_write(&self) {
+                    near_sdk::env::state_write(self);
+                    if near_sdk::env::state_version() != 2u32 {
+                        near_sdk::env::set_state_version(2u32);
+                    }
+                }
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn lazy_state_errors() {
+        let args: BindgenArgs = parse_quote! { lazy_state };
+        let mut s: ItemStruct = parse_quote! { pub struct Hello { value: u64 } };
+        let err = ItemStructInfo::new(&mut s, &args).err().unwrap();
+        assert_eq!(err.to_string(), "Fields of the contract with `lazy_state` should be `Lazy<T>`.");
+
+        let mut s: ItemStruct = parse_quote! { pub struct Hello {} };
+        let err = ItemStructInfo::new(&mut s, &args).err().unwrap();
+        assert_eq!(err.to_string(), "Contract with `lazy_state` should have fields.");
+
+        let args: BindgenArgs = parse_quote! { serializer = borsh };
+        let mut s: ItemStruct = parse_quote! { pub struct Hello { value: u64 } };
+        let err = ItemStructInfo::new(&mut s, &args).err().unwrap();
+        assert_eq!(err.to_string(), "Only `lazy_state`, `state_key` and `state_version` arguments can be used on the contract struct.");
+    }
+}
@@ -10,12 +10,40 @@ impl ItemTraitInfo {
             result.extend(method.method_wrapper());
         }
         let mod_name = &self.mod_name;
+        let client = if self.client { self.client_struct() } else { TokenStream2::new() };
         quote! {
             mod #mod_name {
                 use super::*;
                 use near_sdk::{Gas, Balance, AccountId, Promise};
-                use std::string::ToString;
                 #result
+                #client
+            }
+        }
+    }
+
+    /// Generate `Client` that calls the methods of the contract from outside of the blockchain
+    /// through `near_sdk::client::Transport`. It is only available when compiled natively.
+    pub fn client_struct(&self) -> TokenStream2 {
+        let methods: Vec<_> = self.methods.iter().map(|method| method.client_method()).collect();
+        quote! {
+            /// Client that calls the methods of the contract through the transport.
+            #[cfg(not(target_arch = "wasm32"))]
+            pub struct Client<T> {
+                /// The account of the contract.
+                pub contract_id: AccountId,
+                /// Gas attached to the calls of the methods that are not view methods.
+                pub gas: Gas,
+                pub transport: T,
+            }
+
+            #[cfg(not(target_arch = "wasm32"))]
+            impl<T: near_sdk::client::Transport> Client<T> {
+                /// Create a client of the contract deployed on `contract_id` account.
+                pub fn connect(contract_id: AccountId, transport: T) -> Self {
+                    Self { contract_id, gas: near_sdk::DEFAULT_FUNCTION_CALL_GAS, transport }
+                }
+
+                #(#methods)*
             }
         }
     }
@@ -25,7 +53,7 @@ impl ItemTraitInfo {
 #[rustfmt::skip]
 #[cfg(test)]
 mod tests {
-    use syn::ItemTrait;
+    use syn::{parse_quote, ItemTrait};
     use quote::quote;
     use crate::info_extractor::ItemTraitInfo;
 
@@ -47,20 +75,14 @@ mod tests {
                     }
             }
         ).unwrap();
-        let info = ItemTraitInfo::new(&mut t, None).unwrap();
+        let info = ItemTraitInfo::new(&mut t, &Default::default()).unwrap();
         let actual = info.wrapped_module();
 
         let expected = quote! {
             mod external_cross_contract {
                 use super::*;
                 use near_sdk::{Gas, Balance, AccountId, Promise};
-                use std::string::ToString;
-                pub fn merge_sort<T: ToString>(
-                    arr: Vec<u8>,
-                    __account_id: &T,
-                    __balance: near_sdk::Balance,
-                    __gas: near_sdk::Gas
-                ) -> near_sdk::Promise {
+                pub fn merge_sort(arr: Vec<u8>,) -> near_sdk::FunctionCallBuilder<PromiseOrValue<Vec<u8> >, near_sdk::NoCallback> {
                     #[derive(serde :: Deserialize, serde :: Serialize)]
                     struct Input {
                         arr: Vec<u8>,
@@ -68,24 +90,152 @@ mod tests {
                     let args = Input { arr, };
                     let args = serde_json::to_vec(&args)
                         .expect("Failed to serialize the cross contract args using JSON.");
-                    near_sdk::Promise::new(__account_id.to_string()).function_call(
-                        b"merge_sort".to_vec(),
-                        args,
-                        __balance,
-                        __gas,
-                    )
+                    near_sdk::FunctionCallBuilder::new(b"merge_sort".to_vec(), args)
                 }
-                pub fn merge<T: ToString>(__account_id: &T, __balance: near_sdk::Balance, __gas: near_sdk::Gas) -> near_sdk::Promise {
+                pub fn merge() -> near_sdk::FunctionCallBuilder<Vec<u8>, near_sdk::Callback<(Vec<u8>, Vec<u8>)> > {
                     let args = vec![];
-                    near_sdk::Promise::new(__account_id.to_string()).function_call(
-                        b"merge".to_vec(),
-                        args,
-                        __balance,
-                        __gas,
-                    )
+                    near_sdk::FunctionCallBuilder::new(b"merge".to_vec(), args)
                 }
             }
         };
         assert_eq!(actual.to_string(), expected.to_string());
     }
+
+    #[test]
+    fn argument_serializer() {
+        let mut t: ItemTrait = syn::parse2(
+            quote!{
+                    pub trait Storage {
+                        fn set(&mut self, #[serializer(borsh)] value: u64);
+                    }
+            }
+        ).unwrap();
+        let info = ItemTraitInfo::new(&mut t, &Default::default()).unwrap();
+        let actual = info.methods[0].method_wrapper();
+
+        let expected = quote! {
+            pub fn set(value: u64,) -> near_sdk::FunctionCallBuilder<(), near_sdk::NoCallback> {
+                #[derive(borsh::BorshDeserialize, borsh::BorshSerialize)]
+                struct Input {
+                    value: u64,
+                }
+                let args = Input { value, };
+                let args = borsh::BorshSerialize::try_to_vec(&args)
+                    .expect("Failed to serialize the cross contract args using Borsh.");
+                near_sdk::FunctionCallBuilder::new(b"set".to_vec(), args)
+            }
+        };
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn renamed_method() {
+        let mut t: ItemTrait = syn::parse2(
+            quote!{
+                    pub trait FungibleToken {
+                        #[near_bindgen(rename = "ft_balance_of")]
+                        fn balance_of(&self);
+                    }
+            }
+        ).unwrap();
+        let info = ItemTraitInfo::new(&mut t, &Default::default()).unwrap();
+        let actual = info.wrapped_module();
+
+        let expected = quote! {
+            mod fungible_token {
+                use super::*;
+                use near_sdk::{Gas, Balance, AccountId, Promise};
+                pub fn balance_of() -> near_sdk::FunctionCallBuilder<(), near_sdk::NoCallback> {
+                    let args = vec![];
+                    near_sdk::FunctionCallBuilder::new(b"ft_balance_of".to_vec(), args)
+                }
+            }
+        };
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn client() {
+        let mut t: ItemTrait = syn::parse2(
+            quote!{
+                    pub trait ExtStatusMessage {
+                        #[payable]
+                        #[near_bindgen(rename = "set")]
+                        fn set_status(&mut self, #[serializer(borsh)] message: String);
+                        #[result_serializer(borsh)]
+                        fn get_status(&self, account_id: &str) -> PromiseOrValue<Option<String>>;
+                        fn deploy(&mut self, name: String) -> Promise;
+                    }
+            }
+        ).unwrap();
+        let info = ItemTraitInfo::new(&mut t, &parse_quote! { client }).unwrap();
+        let actual = info.wrapped_module();
+        let methods: Vec<_> = info.methods.iter().map(|method| method.method_wrapper()).collect();
+
+        let expected = quote! {
+            mod ext_status_message {
+            use super::*;
+            use near_sdk::{Gas, Balance, AccountId, Promise};
+            #(#methods)*
+            /// Client that calls the methods of the contract through the transport.
+            #[cfg(not(target_arch = "wasm32"))]
+            pub struct Client<T> {
+                /// The account of the contract.
+                pub contract_id: AccountId,
+                /// Gas attached to the calls of the methods that are not view methods.
+                pub gas: Gas,
+                pub transport: T,
+            }
+
+            #[cfg(not(target_arch = "wasm32"))]
+            impl<T: near_sdk::client::Transport> Client<T> {
+                /// Create a client of the contract deployed on `contract_id` account.
+                pub fn connect(contract_id: AccountId, transport: T) -> Self {
+                    Self { contract_id, gas: near_sdk::DEFAULT_FUNCTION_CALL_GAS, transport }
+                }
+
+                pub fn set_status(&self, message: String, attached_deposit: Balance,) -> Result<(), near_sdk::client::ClientError<T::Error>> {
+                    #[derive(borsh::BorshDeserialize, borsh::BorshSerialize)]
+                    struct Input {
+                        message: String,
+                    }
+                    let args = Input { message, };
+                    let args = borsh::BorshSerialize::try_to_vec(&args)
+                        .expect("Failed to serialize the cross contract args using Borsh.");
+                    self.transport.call(&self.contract_id, "set", args, attached_deposit, self.gas)
+                        .map_err(near_sdk::client::ClientError::Transport)?;
+                    Ok(())
+                }
+
+                pub fn get_status(&self, account_id: String,) -> Result<Option<String>, near_sdk::client::ClientError<T::Error>> {
+                    #[derive(serde :: Deserialize, serde :: Serialize)]
+                    struct Input {
+                        account_id: String,
+                    }
+                    let args = Input { account_id, };
+                    let args = serde_json::to_vec(&args)
+                        .expect("Failed to serialize the cross contract args using JSON.");
+                    let result = self.transport.view(&self.contract_id, "get_status", args)
+                        .map_err(near_sdk::client::ClientError::Transport)?;
+                    borsh::BorshDeserialize::try_from_slice(&result)
+                        .map_err(|err| err.to_string())
+                        .map_err(near_sdk::client::ClientError::Deserialization)
+                }
+
+                pub fn deploy(&self, name: String,) -> Result<Vec<u8>, near_sdk::client::ClientError<T::Error>> {
+                    #[derive(serde :: Deserialize, serde :: Serialize)]
+                    struct Input {
+                        name: String,
+                    }
+                    let args = Input { name, };
+                    let args = serde_json::to_vec(&args)
+                        .expect("Failed to serialize the cross contract args using JSON.");
+                    self.transport.call(&self.contract_id, "deploy", args, 0, self.gas)
+                        .map_err(near_sdk::client::ClientError::Transport)
+                }
+            }
+            }
+        };
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
 }
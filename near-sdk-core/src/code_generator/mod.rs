@@ -12,3 +12,7 @@ pub use trait_item_method_info::*;
 
 mod item_impl_info;
 pub use item_impl_info::*;
+
+mod item_struct_info;
+pub(crate) mod owned_type;
+mod serializer_type;
@@ -0,0 +1,55 @@
+use syn::fold::Fold;
+use syn::{parse_quote, Type};
+
+/// Replaces borrowed types with their owned counterparts, e.g. `&'a str` with `String` and `&[T]`
+/// with `Vec<T>`. This allows using the types of the method arguments and of the return value in
+/// structs and promises that outlive the borrowed data. Optionally replaces `Self` with the type of
+/// the contract.
+struct OwnedType<'a> {
+    self_ty: Option<&'a Type>,
+}
+
+impl<'a> Fold for OwnedType<'a> {
+    fn fold_type(&mut self, ty: Type) -> Type {
+        match ty {
+            Type::Reference(r) => self.fold_type(*r.elem),
+            Type::Paren(p) => self.fold_type(*p.elem),
+            Type::Slice(s) => {
+                let elem = self.fold_type(*s.elem);
+                parse_quote! { Vec<#elem> }
+            }
+            Type::Path(p) if p.qself.is_none() && p.path.is_ident("str") => {
+                parse_quote! { String }
+            }
+            Type::Path(p) if p.qself.is_none() && p.path.is_ident("Self") => match self.self_ty {
+                Some(self_ty) => self_ty.clone(),
+                None => Type::Path(p),
+            },
+            ty => syn::fold::fold_type(self, ty),
+        }
+    }
+}
+
+/// Get the owned version of the type, see `OwnedType`.
+pub fn owned_type(ty: &Type, self_ty: Option<&Type>) -> Type {
+    OwnedType { self_ty }.fold_type(ty.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::owned_type;
+    use quote::quote;
+    use syn::{parse_quote, Type};
+
+    #[test]
+    fn borrowed_types() {
+        let ty: Type = parse_quote! { Option<&'a str> };
+        let actual = owned_type(&ty, None);
+        assert_eq!(quote! { #actual }.to_string(), quote! { Option<String> }.to_string());
+
+        let ty: Type = parse_quote! { &[(u64, &Self)] };
+        let self_ty: Type = parse_quote! { Hello };
+        let actual = owned_type(&ty, Some(&self_ty));
+        assert_eq!(quote! { #actual }.to_string(), quote! { Vec<(u64, Hello)> }.to_string());
+    }
+}
@@ -0,0 +1,126 @@
+use crate::info_extractor::SerializerType;
+use quote::quote;
+use syn::export::TokenStream2;
+
+impl SerializerType {
+    /// The variant of `near_sdk::abi::SerializerType` that describes this format in the ABI.
+    pub fn abi_variant(&self) -> TokenStream2 {
+        match self {
+            SerializerType::JSON => quote! { near_sdk::abi::SerializerType::JSON },
+            SerializerType::Borsh => quote! { near_sdk::abi::SerializerType::Borsh },
+            SerializerType::Raw => quote! { near_sdk::abi::SerializerType::Raw },
+            #[cfg(feature = "msgpack")]
+            SerializerType::MessagePack => quote! { near_sdk::abi::SerializerType::MessagePack },
+            #[cfg(feature = "cbor")]
+            SerializerType::CBOR => quote! { near_sdk::abi::SerializerType::CBOR },
+        }
+    }
+
+    /// The name of the serialization format used in the error messages.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SerializerType::JSON => "JSON",
+            SerializerType::Borsh => "Borsh",
+            SerializerType::Raw => "raw bytes",
+            #[cfg(feature = "msgpack")]
+            SerializerType::MessagePack => "MessagePack",
+            #[cfg(feature = "cbor")]
+            SerializerType::CBOR => "CBOR",
+        }
+    }
+
+    /// Create expression that deserializes the value from `data`, which is an expression of type
+    /// `&Vec<u8>` or `&[u8]`. If deserialization fails, it panics with `message` followed by the
+    /// deserialization error. `message` is a format string whose arguments are `message_args`,
+    /// e.g. `quote! { i, }`.
+    pub fn deserialize_expr(
+        &self,
+        data: TokenStream2,
+        message: &str,
+        message_args: TokenStream2,
+    ) -> TokenStream2 {
+        let message = format!("{}: {{}}", message);
+        let on_error = quote! {
+            unwrap_or_else(|err| near_sdk::env::panic(format!(#message, #message_args err).as_bytes()))
+        };
+        match self {
+            SerializerType::JSON => quote! {
+                serde_json::from_slice(#data).#on_error
+            },
+            SerializerType::Borsh => quote! {
+                borsh::BorshDeserialize::try_from_slice(#data).#on_error
+            },
+            SerializerType::Raw => quote! {
+                <[u8]>::to_vec(#data)
+            },
+            #[cfg(feature = "msgpack")]
+            SerializerType::MessagePack => quote! {
+                near_sdk::rmp_serde::from_slice(#data).#on_error
+            },
+            #[cfg(feature = "cbor")]
+            SerializerType::CBOR => quote! {
+                near_sdk::serde_cbor::from_slice(#data).#on_error
+            },
+        }
+    }
+
+    /// Create expression of type `Result<T, String>` that deserializes the value from `data`, which
+    /// is an expression of type `&Vec<u8>` or `&[u8]`, without panicking.
+    pub fn try_deserialize_expr(&self, data: TokenStream2) -> TokenStream2 {
+        match self {
+            SerializerType::JSON => quote! {
+                serde_json::from_slice(#data).map_err(|err| err.to_string())
+            },
+            SerializerType::Borsh => quote! {
+                borsh::BorshDeserialize::try_from_slice(#data).map_err(|err| err.to_string())
+            },
+            SerializerType::Raw => quote! {
+                Ok(<[u8]>::to_vec(#data))
+            },
+            #[cfg(feature = "msgpack")]
+            SerializerType::MessagePack => quote! {
+                near_sdk::rmp_serde::from_slice(#data).map_err(|err| err.to_string())
+            },
+            #[cfg(feature = "cbor")]
+            SerializerType::CBOR => quote! {
+                near_sdk::serde_cbor::from_slice(#data).map_err(|err| err.to_string())
+            },
+        }
+    }
+
+    /// Create expression that serializes `value`, which is an expression of a reference type, into
+    /// `Vec<u8>` and panics with `message` if serialization fails.
+    pub fn serialize_expr(&self, value: TokenStream2, message: &str) -> TokenStream2 {
+        match self {
+            SerializerType::JSON => quote! {
+                serde_json::to_vec(#value).expect(#message)
+            },
+            SerializerType::Borsh => quote! {
+                borsh::BorshSerialize::try_to_vec(#value).expect(#message)
+            },
+            SerializerType::Raw => quote! {
+                <[u8]>::to_vec(#value)
+            },
+            #[cfg(feature = "msgpack")]
+            SerializerType::MessagePack => quote! {
+                near_sdk::rmp_serde::to_vec_named(#value).expect(#message)
+            },
+            #[cfg(feature = "cbor")]
+            SerializerType::CBOR => quote! {
+                near_sdk::serde_cbor::to_vec(#value).expect(#message)
+            },
+        }
+    }
+
+    /// Attribute that derives the (de)serialization of the struct with the input arguments.
+    pub fn derive_attr(&self) -> TokenStream2 {
+        match self {
+            SerializerType::Borsh => {
+                quote! {#[derive(borsh::BorshDeserialize, borsh::BorshSerialize)]}
+            }
+            // Raw input is not wrapped into a struct when it is deserialized.
+            SerializerType::Raw => TokenStream2::new(),
+            _ => quote! {#[derive(serde::Deserialize, serde::Serialize)]},
+        }
+    }
+}
@@ -1,47 +1,122 @@
-use crate::info_extractor::{SerializerType, TraitItemMethodInfo};
+use crate::code_generator::owned_type::owned_type;
+use crate::info_extractor::TraitItemMethodInfo;
 use quote::quote;
 use syn::export::TokenStream2;
+use syn::{GenericArgument, PathArguments, ReturnType, Type};
 
 impl TraitItemMethodInfo {
-    /// Generate code that wraps the method.
+    /// Generate function that serializes the arguments of the method and returns a builder of
+    /// the call. The arguments are serialized with the serializer set with `#[serializer(...)]` or
+    /// the default serializer of the trait, the same way the called method reads them.
     pub fn method_wrapper(&self) -> TokenStream2 {
         let ident = &self.attr_sig_info.ident;
-        let ident_byte_str = &self.ident_byte_str;
-        let pat_type_list = self.attr_sig_info.pat_type_list();
-        let has_input_args = self.attr_sig_info.input_args().next().is_some();
-        let struct_decl;
-        let constructor;
-        let value_ser;
-        if !has_input_args {
-            struct_decl = TokenStream2::new();
-            constructor = TokenStream2::new();
-            value_ser = quote! {let args = vec![]; };
+        let arg_list = self.attr_sig_info.owned_arg_list();
+        let output = self.attr_sig_info.owned_output_type(None);
+        let input = self.attr_sig_info.callback_input_type(None);
+        let body = self
+            .attr_sig_info
+            .function_call_builder(&self.ident_byte_str, self.attr_sig_info.input_serializer);
+        quote! {
+            pub fn #ident(#arg_list) -> near_sdk::FunctionCallBuilder<#output, #input> {
+                #body
+            }
+        }
+    }
+
+    /// Generate method of the client that calls the method through the transport and deserializes
+    /// its result. Payable methods take the attached deposit as the last argument, and methods that
+    /// return `Promise` return the raw bytes of its result.
+    pub fn client_method(&self) -> TokenStream2 {
+        let attr_sig_info = &self.attr_sig_info;
+        let ident = &attr_sig_info.ident;
+        let method_name = attr_sig_info.exported_name.to_string();
+        let arg_list = attr_sig_info.owned_arg_list();
+        let args_serialization = attr_sig_info.args_serialization(attr_sig_info.input_serializer);
+        let (deposit_arg, deposit) = if attr_sig_info.is_payable {
+            (quote! { attached_deposit: Balance, }, quote! { attached_deposit })
+        } else {
+            (TokenStream2::new(), quote! { 0 })
+        };
+        let execution = if attr_sig_info.is_view {
+            quote! { self.transport.view(&self.contract_id, #method_name, args) }
         } else {
-            struct_decl = self.attr_sig_info.input_struct();
-            let constructor_call = self.attr_sig_info.constructor_expr();
-            constructor = quote! {let args = #constructor_call;};
-            value_ser = match self.attr_sig_info.result_serializer {
-                SerializerType::JSON => quote! {
-                    let args = serde_json::to_vec(&args).expect("Failed to serialize the cross contract args using JSON.");
+            quote! {
+                self.transport.call(&self.contract_id, #method_name, args, #deposit, self.gas)
+            }
+        };
+        let (output, result) = match self.client_output_type() {
+            // The result of an untyped promise is returned as is.
+            Some(output) if is_untyped_promise(&output) => (
+                quote! { Vec<u8> },
+                quote! {
+                    #execution.map_err(near_sdk::client::ClientError::Transport)
                 },
-                SerializerType::Borsh => quote! {
-                    let args = borsh::BorshSerialize::try_to_vec(&args).expect("Failed to serialize the cross contract args using Borsh.");
+            ),
+            Some(output) => {
+                let deserialization =
+                    attr_sig_info.result_serializer.try_deserialize_expr(quote! { &result });
+                (
+                    quote! { #output },
+                    quote! {
+                        let result = #execution.map_err(near_sdk::client::ClientError::Transport)?;
+                        #deserialization.map_err(near_sdk::client::ClientError::Deserialization)
+                    },
+                )
+            }
+            None => (
+                quote! { () },
+                quote! {
+                    #execution.map_err(near_sdk::client::ClientError::Transport)?;
+                    Ok(())
                 },
-            };
-        }
+            ),
+        };
         quote! {
-            pub fn #ident<T: ToString>(#pat_type_list __account_id: &T, __balance: near_sdk::Balance, __gas: near_sdk::Gas) -> near_sdk::Promise {
-                #struct_decl
-                #constructor
-                #value_ser
-                near_sdk::Promise::new(__account_id.to_string())
-                .function_call(
-                    #ident_byte_str.to_vec(),
-                    args,
-                    __balance,
-                    __gas,
-                )
+            pub fn #ident(&self, #arg_list #deposit_arg) -> Result<#output, near_sdk::client::ClientError<T::Error>> {
+                #args_serialization
+                #result
+            }
+        }
+    }
+
+    /// The type of the value that the client receives, i.e. the type of the value that the promise
+    /// returned by the method eventually produces. `None` if the method returns nothing or is an
+    /// init method.
+    fn client_output_type(&self) -> Option<Type> {
+        match &self.attr_sig_info.returns {
+            ReturnType::Type(_, ty) if !self.attr_sig_info.is_init => {
+                Some(owned_type(promised_type(ty), None))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Whether the type is `Promise`, whose result has no type to deserialize it into.
+fn is_untyped_promise(ty: &Type) -> bool {
+    match ty {
+        Type::Path(type_path) if type_path.qself.is_none() => {
+            match type_path.path.segments.last() {
+                Some(segment) => segment.ident == "Promise" && segment.arguments.is_empty(),
+                None => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// `T` for `PromiseOrValue<T>` and `TypedPromise<T>`, and the type itself otherwise.
+fn promised_type(ty: &Type) -> &Type {
+    if let Type::Path(type_path) = ty {
+        if let Some(segment) = type_path.path.segments.last() {
+            if segment.ident == "PromiseOrValue" || segment.ident == "TypedPromise" {
+                if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                    if let Some(GenericArgument::Type(ty)) = args.args.first() {
+                        return ty;
+                    }
+                }
             }
         }
     }
+    ty
 }
@@ -1,9 +1,13 @@
 use crate::info_extractor::serializer_attr::SerializerAttr;
-use crate::info_extractor::SerializerType;
+use crate::info_extractor::{SerializerType, ValidateAttr, Validator};
 use quote::ToTokens;
 use syn::export::Span;
-use syn::{Attribute, Error, Ident, Pat, PatType, Token, Type};
+use syn::spanned::Spanned;
+use syn::{
+    Attribute, Error, Expr, GenericArgument, Ident, Pat, PatType, PathArguments, Token, Type,
+};
 
+#[derive(PartialEq, Eq)]
 pub enum BindgenArgType {
     /// Argument that we read from `env::input()`.
     Regular,
@@ -11,6 +15,8 @@ pub enum BindgenArgType {
     CallbackArg,
     /// An argument that we read from all `env::promise_result()`.
     CallbackArgVec,
+    /// The old state of the contract that the migration method converts, read from `STATE`.
+    OldState,
 }
 
 /// A single argument of a function after it was processed by the bindgen.
@@ -33,13 +39,20 @@ pub struct ArgInfo {
     pub bindgen_ty: BindgenArgType,
     /// Type of serializer that we use for this argument.
     pub serializer_ty: SerializerType,
+    /// The expression from `#[default(expr)]` used when the argument is missing in the input.
+    pub default: Option<Expr>,
+    /// `#[serde(...)]` attributes that are passed to the field of the input struct.
+    pub serde_attrs: Vec<Attribute>,
+    /// Checks from `#[validate(...)]` attributes done before the method is called.
+    pub validators: Vec<Validator>,
     /// The original `PatType` of the argument.
     pub original: PatType,
 }
 
 impl ArgInfo {
-    /// Extract near-sdk specific argument info.
-    pub fn new(original: &mut PatType) -> syn::Result<Self> {
+    /// Extract near-sdk specific argument info. Arguments without `#[serializer(...)]` use
+    /// `default_serializer`.
+    pub fn new(original: &mut PatType, default_serializer: SerializerType) -> syn::Result<Self> {
         let mut non_bindgen_attrs = vec![];
         let pat_reference;
         let pat_mutability;
@@ -68,8 +81,12 @@ impl ArgInfo {
         };
         // In the absence of callback attributes this is a regular argument.
         let mut bindgen_ty = BindgenArgType::Regular;
-        // In the absence of serialization attributes this is a JSON serialization.
-        let mut serializer_ty = SerializerType::JSON;
+        // In the absence of serialization attributes we use the default serialization.
+        let mut serializer_ty = default_serializer;
+        let mut default = None;
+        let mut serde_attrs = vec![];
+        let mut serde_attr_span = None;
+        let mut validators = vec![];
         for attr in &mut original.attrs {
             let attr_str = attr.path.to_token_stream().to_string();
             match attr_str.as_str() {
@@ -83,15 +100,55 @@ impl ArgInfo {
                     let serializer: SerializerAttr = syn::parse2(attr.tokens.clone())?;
                     serializer_ty = serializer.serializer_type;
                 }
+                "default" => {
+                    default = Some(attr.parse_args()?);
+                    serde_attr_span = Some(attr.span());
+                }
+                "validate" => {
+                    let validate: ValidateAttr = syn::parse2(attr.tokens.clone())?;
+                    validators.extend(validate.validators);
+                }
+                "serde" => {
+                    serde_attrs.push((*attr).clone());
+                    serde_attr_span = Some(attr.span());
+                }
                 _ => {
                     non_bindgen_attrs.push((*attr).clone());
                 }
             }
         }
 
+        if serializer_ty == SerializerType::Raw && !is_bytes(&ty) {
+            return Err(Error::new(
+                original.ty.span(),
+                "Raw serializer can only be used with arguments of type `Vec<u8>` or `&[u8]`.",
+            ));
+        }
+
+        if let Some(span) = serde_attr_span {
+            if bindgen_ty != BindgenArgType::Regular {
+                return Err(Error::new(
+                    span,
+                    "`#[default]` and `#[serde]` can only be used with arguments read from the input.",
+                ));
+            }
+            if !serializer_ty.is_serde() {
+                return Err(Error::new(
+                    span,
+                    "`#[default]` and `#[serde]` can only be used with arguments serialized with \
+                     serde, e.g. JSON.",
+                ));
+            }
+        }
+
         original.attrs.retain(|attr| {
             let attr_str = attr.path.to_token_stream().to_string();
-            attr_str != "callback" && attr_str != "callback_vec" && attr_str != "serializer"
+            attr_str != "callback"
+                && attr_str != "callback_vec"
+                && attr_str != "serializer"
+                && attr_str != "default"
+                && attr_str != "serde"
+                && attr_str != "validate"
         });
 
         Ok(Self {
@@ -104,7 +161,37 @@ impl ArgInfo {
             ty,
             bindgen_ty,
             serializer_ty,
+            default,
+            serde_attrs,
+            validators,
             original: original.clone(),
         })
     }
 }
+
+/// Whether the type is `Vec<u8>` or `[u8]`.
+pub(crate) fn is_bytes(ty: &Type) -> bool {
+    let is_u8 = |ty: &Type| match ty {
+        Type::Path(p) => p.qself.is_none() && p.path.is_ident("u8"),
+        _ => false,
+    };
+    match ty {
+        Type::Slice(s) => is_u8(&s.elem),
+        Type::Path(p) if p.qself.is_none() => {
+            let segment = match p.path.segments.last() {
+                Some(segment) if segment.ident == "Vec" => segment,
+                _ => return false,
+            };
+            match &segment.arguments {
+                PathArguments::AngleBracketed(args) if args.args.len() == 1 => {
+                    match &args.args[0] {
+                        GenericArgument::Type(ty) => is_u8(ty),
+                        _ => false,
+                    }
+                }
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
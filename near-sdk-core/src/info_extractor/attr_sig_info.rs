@@ -1,27 +1,42 @@
-use crate::info_extractor::arg_info::{ArgInfo, BindgenArgType};
+use crate::info_extractor::arg_info::{is_bytes, ArgInfo, BindgenArgType};
+use crate::info_extractor::bindgen_args::method_name;
 use crate::info_extractor::serializer_attr::SerializerAttr;
-use crate::info_extractor::SerializerType;
+use crate::info_extractor::{InitArgs, MethodBindgenArgs, SerializerType};
 use quote::ToTokens;
 use syn::export::Span;
+use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
-use syn::{Attribute, Error, FnArg, Ident, Receiver, ReturnType, Signature};
+use syn::{Attribute, Error, FnArg, Ident, LitStr, Receiver, ReturnType, Signature, Token, Type};
 
 /// Information extracted from method attributes and signature.
 pub struct AttrSigInfo {
     /// The name of the method.
     pub ident: Ident,
+    /// The name under which the method is exported, i.e. `ident` unless it is overridden with
+    /// `#[near_bindgen(rename = "...")]`.
+    pub exported_name: Ident,
+    /// Additional names under which the method is exported, set with `#[alias("...")]`. They are
+    /// not included in the metadata.
+    pub aliases: Vec<Ident>,
     /// Attributes not related to bindgen.
     pub non_bindgen_attrs: Vec<Attribute>,
     /// All arguments of the method.
     pub args: Vec<ArgInfo>,
     /// Whether method can be used as initializer.
     pub is_init: bool,
+    /// The type of the old state that is read from `STATE` and passed as the first argument to the
+    /// migration method, set with `#[migrate]` or `#[init(migrate_from = ...)]`.
+    pub migrate_from: Option<Type>,
     /// Whether method accepting $NEAR.
     pub is_payable: bool,
     /// The serializer that we use for `env::input()`.
     pub input_serializer: SerializerType,
-    /// Whether the method doesn't mutate state
+    /// Whether the method doesn't mutate state. Set explicitly with `#[view]` or `#[call]`,
+    /// otherwise inferred from the receiver.
     pub is_view: bool,
+    /// Whether the method is marked with `#[view]`, in which case the wrapper checks that it does
+    /// not change the state.
+    pub has_view_attr: bool,
     /// The serializer that we use for the return type.
     pub result_serializer: SerializerType,
     /// The receiver, like `mut self`, `self`, `&mut self`, `&self`, or `None`.
@@ -33,10 +48,12 @@ pub struct AttrSigInfo {
 }
 
 impl AttrSigInfo {
-    /// Process the method and extract information important for near-sdk.
+    /// Process the method and extract information important for near-sdk. Arguments and the result
+    /// without a serializer attribute use `default_serializer`.
     pub fn new(
         original_attrs: &mut Vec<Attribute>,
         original_sig: &mut Signature,
+        default_serializer: SerializerType,
     ) -> syn::Result<Self> {
         if original_sig.asyncness.is_some() {
             return Err(Error::new(
@@ -58,24 +75,57 @@ impl AttrSigInfo {
         }
 
         let ident = original_sig.ident.clone();
+        let mut exported_name = ident.clone();
+        let mut aliases = vec![];
         let mut non_bindgen_attrs = vec![];
         let mut args = vec![];
         let mut is_init = false;
+        let mut migrate_from = None;
         let mut is_payable = false;
-        // By the default we serialize the result with JSON.
-        let mut result_serializer = SerializerType::JSON;
+        // In the absence of `#[result_serializer(...)]` we use the default serialization.
+        let mut result_serializer = default_serializer;
 
         let mut payable_attr = None;
+        let mut view_attr = None;
+        let mut call_attr = None;
+        let mut migrate_attr = None;
         for attr in original_attrs.iter() {
             let attr_str = attr.path.to_token_stream().to_string();
             match attr_str.as_str() {
                 "init" => {
                     is_init = true;
+                    if !attr.tokens.is_empty() {
+                        let init_args: InitArgs = attr.parse_args()?;
+                        migrate_from = init_args.migrate_from;
+                    }
+                }
+                "migrate" => {
+                    is_init = true;
+                    migrate_attr = Some(attr);
                 }
                 "payable" => {
                     payable_attr = Some(attr);
                     is_payable = true;
                 }
+                "near_bindgen" => {
+                    let method_args: MethodBindgenArgs = attr.parse_args()?;
+                    if let Some(rename) = method_args.rename {
+                        exported_name = rename;
+                    }
+                }
+                "alias" => {
+                    let names =
+                        attr.parse_args_with(Punctuated::<LitStr, Token![,]>::parse_terminated)?;
+                    for name in &names {
+                        aliases.push(method_name(name)?);
+                    }
+                }
+                "view" => {
+                    view_attr = Some(attr);
+                }
+                "call" => {
+                    call_attr = Some(attr);
+                }
                 "result_serializer" => {
                     let serializer: SerializerAttr = syn::parse2(attr.tokens.clone())?;
                     result_serializer = serializer.serializer_type;
@@ -91,15 +141,63 @@ impl AttrSigInfo {
             match fn_arg {
                 FnArg::Receiver(r) => receiver = Some((*r).clone()),
                 FnArg::Typed(pat_typed) => {
-                    args.push(ArgInfo::new(pat_typed)?);
+                    args.push(ArgInfo::new(pat_typed, default_serializer)?);
                 }
             }
         }
 
-        let is_view = if let Some(ref receiver) = receiver {
-            receiver.mutability.is_none()
-        } else {
-            !is_init
+        if migrate_attr.is_some() || migrate_from.is_some() {
+            let span = migrate_attr.map(|attr| attr.span()).unwrap_or_else(|| original_sig.span());
+            if migrate_attr.is_some() && migrate_from.is_some() {
+                return Err(Error::new(
+                    span,
+                    "Use either `#[migrate]` or `#[init(migrate_from = ...)]`, not both.",
+                ));
+            }
+            if receiver.is_some() {
+                return Err(Error::new(span, "Migration method cannot take `self`."));
+            }
+            match args.first_mut() {
+                Some(old_state) if old_state.bindgen_ty == BindgenArgType::Regular => {
+                    old_state.bindgen_ty = BindgenArgType::OldState;
+                    if migrate_from.is_none() {
+                        migrate_from = Some(old_state.ty.clone());
+                    }
+                }
+                _ => {
+                    return Err(Error::new(
+                        span,
+                        "Migration method should take the old state as the first argument.",
+                    ));
+                }
+            }
+        }
+
+        let has_view_attr = view_attr.is_some();
+        let is_view = match (view_attr, call_attr) {
+            (Some(_), Some(call_attr)) => {
+                return Err(Error::new(
+                    call_attr.span(),
+                    "Method cannot be both `#[view]` and `#[call]`.",
+                ));
+            }
+            (Some(view_attr), None) => {
+                if is_init {
+                    return Err(Error::new(
+                        view_attr.span(),
+                        "Init method must be mutable (not view)",
+                    ));
+                }
+                true
+            }
+            (None, Some(_)) => false,
+            (None, None) => {
+                if let Some(ref receiver) = receiver {
+                    receiver.mutability.is_none()
+                } else {
+                    !is_init
+                }
+            }
         };
 
         if let Some(payable_attr) = payable_attr {
@@ -113,36 +211,74 @@ impl AttrSigInfo {
 
         original_attrs.retain(|attr| {
             let attr_str = attr.path.to_token_stream().to_string();
-            attr_str != "init" && attr_str != "result_serializer" && attr_str != "payable"
+            !matches!(
+                attr_str.as_str(),
+                "init"
+                    | "migrate"
+                    | "result_serializer"
+                    | "payable"
+                    | "view"
+                    | "call"
+                    | "near_bindgen"
+                    | "alias"
+            )
         });
 
+        if result_serializer == SerializerType::Raw {
+            let is_bytes_result = match &original_sig.output {
+                ReturnType::Type(_, ty) => match ty.as_ref() {
+                    Type::Reference(r) => is_bytes(&r.elem),
+                    ty => is_bytes(ty),
+                },
+                ReturnType::Default => false,
+            };
+            if !is_bytes_result {
+                return Err(Error::new(
+                    original_sig.output.span(),
+                    "Raw result serializer can only be used with methods returning `Vec<u8>` or \
+                     `&[u8]`.",
+                ));
+            }
+        }
+
         let returns = original_sig.output.clone();
 
         let mut result = Self {
             ident,
+            exported_name,
+            aliases,
             non_bindgen_attrs,
             args,
-            input_serializer: SerializerType::JSON,
+            input_serializer: default_serializer,
             is_init,
+            migrate_from,
             is_payable,
             is_view,
+            has_view_attr,
             result_serializer,
             receiver,
             returns,
             original_sig: original_sig.clone(),
         };
 
-        let input_serializer =
-            if result.input_args().all(|arg: &ArgInfo| arg.serializer_ty == SerializerType::JSON) {
-                SerializerType::JSON
-            } else if result.input_args().all(|arg| arg.serializer_ty == SerializerType::Borsh) {
-                SerializerType::Borsh
-            } else {
-                return Err(Error::new(
-                    Span::call_site(),
-                    format!("Input arguments should be all of the same serialization type."),
-                ));
-            };
+        let input_serializer = match result.input_args().next() {
+            None => default_serializer,
+            Some(first) => {
+                if result.input_args().any(|arg| arg.serializer_ty != first.serializer_ty) {
+                    return Err(Error::new(
+                        Span::call_site(),
+                        format!("Input arguments should be all of the same serialization type."),
+                    ));
+                }
+                first.serializer_ty
+            }
+        };
+        if input_serializer == SerializerType::Raw && result.input_args().count() != 1 {
+            return Err(Error::new(
+                original_sig.inputs.span(),
+                "Raw serializer requires the method to have exactly one input argument.",
+            ));
+        }
         result.input_serializer = input_serializer;
         Ok(result)
     }
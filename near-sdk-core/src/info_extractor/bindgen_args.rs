@@ -0,0 +1,330 @@
+use crate::info_extractor::serializer_attr::serializer_type;
+use crate::info_extractor::SerializerType;
+use proc_macro2::Ident;
+use syn::parse::{Parse, ParseStream};
+use syn::{Error, LitBool, LitInt, LitStr, Token, Type};
+
+/// Arguments of the `#[near_bindgen(...)]` attribute, e.g. `#[near_bindgen(serializer = borsh)]`.
+#[derive(Default)]
+pub struct BindgenArgs {
+    /// The serializer used for the arguments and the results of the methods that do not override
+    /// it with `#[serializer(...)]` or `#[result_serializer(...)]`.
+    pub serializer: Option<SerializerType>,
+    /// The concrete type, e.g. `TokenContract<DefaultPolicy>`, for which the methods of a generic
+    /// `impl` section are exported.
+    pub instantiate: Option<Type>,
+    /// Whether JSON arguments can be passed as a positional array, e.g. `[1, "a"]`, in addition to
+    /// an object, e.g. `{"n": 1, "s": "a"}`. Enabled by default.
+    pub positional_args: Option<bool>,
+    /// Whether each field of the contract struct is stored under its own key and is read only when
+    /// it is used. Only used on the contract struct.
+    pub lazy_state: bool,
+    /// The key under which the state of the contract is stored instead of `STATE`. Only used on the
+    /// contract struct.
+    pub state_key: Option<LitStr>,
+    /// The version of the state layout that is stored with the state and checked when the state
+    /// is read. Only used on the contract struct.
+    pub state_version: Option<u32>,
+}
+
+impl BindgenArgs {
+    /// The serializer used by the methods by default.
+    pub fn default_serializer(&self) -> SerializerType {
+        self.serializer.unwrap_or(SerializerType::JSON)
+    }
+}
+
+impl Parse for BindgenArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut result = Self::default();
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            match key.to_string().as_str() {
+                "serializer" => {
+                    if result.serializer.is_some() {
+                        return Err(Error::new(key.span(), "Duplicate `serializer` argument."));
+                    }
+                    input.parse::<Token![=]>()?;
+                    let value: Ident = input.parse()?;
+                    result.serializer = Some(serializer_type(&value)?);
+                }
+                "instantiate" => {
+                    if result.instantiate.is_some() {
+                        return Err(Error::new(key.span(), "Duplicate `instantiate` argument."));
+                    }
+                    input.parse::<Token![=]>()?;
+                    let value: LitStr = input.parse()?;
+                    result.instantiate = Some(value.parse()?);
+                }
+                "positional_args" => {
+                    if result.positional_args.is_some() {
+                        return Err(Error::new(
+                            key.span(),
+                            "Duplicate `positional_args` argument.",
+                        ));
+                    }
+                    input.parse::<Token![=]>()?;
+                    let value: LitBool = input.parse()?;
+                    result.positional_args = Some(value.value);
+                }
+                "state_key" => {
+                    if result.state_key.is_some() {
+                        return Err(Error::new(key.span(), "Duplicate `state_key` argument."));
+                    }
+                    input.parse::<Token![=]>()?;
+                    let value: LitStr = input.parse()?;
+                    if value.value().is_empty() {
+                        return Err(Error::new(value.span(), "`state_key` should not be empty."));
+                    }
+                    result.state_key = Some(value);
+                }
+                "state_version" => {
+                    if result.state_version.is_some() {
+                        return Err(Error::new(key.span(), "Duplicate `state_version` argument."));
+                    }
+                    input.parse::<Token![=]>()?;
+                    let value: LitInt = input.parse()?;
+                    result.state_version = Some(value.base10_parse()?);
+                }
+                // The legacy form, e.g. `#[near_bindgen(init => new)]`, that had no effect.
+                "init" if input.peek(Token![=>]) => {
+                    input.parse::<Token![=>]>()?;
+                    input.parse::<Ident>()?;
+                }
+                "lazy_state" => {
+                    if result.lazy_state {
+                        return Err(Error::new(key.span(), "Duplicate `lazy_state` argument."));
+                    }
+                    result.lazy_state = true;
+                }
+                _ => {
+                    return Err(Error::new(
+                        key.span(),
+                        format!("Unsupported near_bindgen argument `{}`.", key),
+                    ))
+                }
+            }
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+        Ok(result)
+    }
+}
+
+/// Arguments of the `#[near_bindgen(...)]` attribute on a method, e.g.
+/// `#[near_bindgen(rename = "ft_transfer")]`.
+#[derive(Default)]
+pub struct MethodBindgenArgs {
+    /// The name under which the method is exported instead of its Rust name.
+    pub rename: Option<Ident>,
+}
+
+impl Parse for MethodBindgenArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut result = Self::default();
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            match key.to_string().as_str() {
+                "rename" => {
+                    if result.rename.is_some() {
+                        return Err(Error::new(key.span(), "Duplicate `rename` argument."));
+                    }
+                    let value: LitStr = input.parse()?;
+                    result.rename = Some(method_name(&value)?);
+                }
+                _ => {
+                    return Err(Error::new(
+                        key.span(),
+                        format!("Unsupported near_bindgen argument `{}` for a method.", key),
+                    ))
+                }
+            }
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+        Ok(result)
+    }
+}
+
+/// Arguments of the `#[init(...)]` attribute, e.g. `#[init(migrate_from = OldState)]`.
+#[derive(Default)]
+pub struct InitArgs {
+    /// The type of the old state that the init method converts to the new one.
+    pub migrate_from: Option<Type>,
+}
+
+impl Parse for InitArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut result = Self::default();
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            match key.to_string().as_str() {
+                "migrate_from" => {
+                    if result.migrate_from.is_some() {
+                        return Err(Error::new(key.span(), "Duplicate `migrate_from` argument."));
+                    }
+                    result.migrate_from = Some(input.parse()?);
+                }
+                _ => {
+                    return Err(Error::new(
+                        key.span(),
+                        format!("Unsupported init argument `{}`.", key),
+                    ))
+                }
+            }
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+        Ok(result)
+    }
+}
+
+/// Parses the exported name of the method, e.g. from `#[alias("old_name")]`.
+pub fn method_name(lit: &LitStr) -> syn::Result<Ident> {
+    lit.parse().map_err(|_| {
+        Error::new(lit.span(), format!("`{}` is not a valid method name.", lit.value()))
+    })
+}
+
+/// Arguments of the `#[ext_contract(...)]` attribute: an optional name of the generated module and
+/// `client` flag followed by the same arguments as `#[near_bindgen(...)]`, e.g.
+/// `#[ext_contract(ext, client, serializer = borsh)]`.
+#[derive(Default)]
+pub struct ExtContractArgs {
+    /// The name of the generated module, if overridden.
+    pub mod_name: Option<Ident>,
+    /// Whether to generate `Client` that calls the contract from outside of the blockchain.
+    pub client: bool,
+    /// The remaining arguments.
+    pub bindgen_args: BindgenArgs,
+}
+
+impl Parse for ExtContractArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut mod_name = None;
+        let mut client = false;
+        while input.peek(syn::Ident) && !input.peek2(Token![=]) {
+            let ident: Ident = input.parse()?;
+            if ident == "client" {
+                if client {
+                    return Err(Error::new(ident.span(), "Duplicate `client` argument."));
+                }
+                client = true;
+            } else if mod_name.is_none() {
+                mod_name = Some(ident);
+            } else {
+                return Err(Error::new(ident.span(), "The module name is already set."));
+            }
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+        let bindgen_args = input.parse()?;
+        Ok(Self { mod_name, client, bindgen_args })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn ext_contract_args() {
+        let args: ExtContractArgs = parse_quote! { ext, serializer = borsh };
+        assert_eq!(args.mod_name.unwrap().to_string(), "ext");
+        assert!(args.bindgen_args.default_serializer() == SerializerType::Borsh);
+
+        let args: ExtContractArgs = parse_quote! { serializer = json };
+        assert!(args.mod_name.is_none());
+        assert!(args.bindgen_args.default_serializer() == SerializerType::JSON);
+
+        let args: ExtContractArgs = parse_quote! {};
+        assert!(args.mod_name.is_none());
+        assert!(args.bindgen_args.serializer.is_none());
+        assert!(!args.client);
+
+        let args: ExtContractArgs = parse_quote! { ext, client, serializer = borsh };
+        assert_eq!(args.mod_name.unwrap().to_string(), "ext");
+        assert!(args.client);
+        assert!(args.bindgen_args.default_serializer() == SerializerType::Borsh);
+
+        let args: ExtContractArgs = parse_quote! { client };
+        assert!(args.mod_name.is_none());
+        assert!(args.client);
+
+        let err = syn::parse_str::<ExtContractArgs>("ext, other").err().unwrap();
+        assert_eq!(err.to_string(), "The module name is already set.");
+    }
+
+    #[test]
+    fn method_args() {
+        let args: MethodBindgenArgs = parse_quote! { rename = "ft_transfer" };
+        assert_eq!(args.rename.unwrap().to_string(), "ft_transfer");
+
+        let err = syn::parse_str::<MethodBindgenArgs>(r#"rename = "ft-transfer""#).err().unwrap();
+        assert_eq!(err.to_string(), "`ft-transfer` is not a valid method name.");
+        let err = syn::parse_str::<MethodBindgenArgs>("serializer = borsh").err().unwrap();
+        assert_eq!(err.to_string(), "Unsupported near_bindgen argument `serializer` for a method.");
+    }
+
+    #[test]
+    fn init_args() {
+        let args: InitArgs = parse_quote! { migrate_from = v1::Contract };
+        let ty: Type = parse_quote! { v1::Contract };
+        assert!(args.migrate_from.unwrap() == ty);
+        let err = syn::parse_str::<InitArgs>("migrate = Old").err().unwrap();
+        assert_eq!(err.to_string(), "Unsupported init argument `migrate`.");
+    }
+
+    #[test]
+    fn lazy_state() {
+        let args: BindgenArgs = parse_quote! { lazy_state };
+        assert!(args.lazy_state);
+        let args: BindgenArgs = parse_quote! { serializer = borsh, lazy_state };
+        assert!(args.lazy_state);
+        let err = syn::parse_str::<BindgenArgs>("lazy_state, lazy_state").err().unwrap();
+        assert_eq!(err.to_string(), "Duplicate `lazy_state` argument.");
+    }
+
+    #[test]
+    fn state_key() {
+        let args: BindgenArgs = parse_quote! { state_key = "CONFIG" };
+        assert_eq!(args.state_key.unwrap().value(), "CONFIG");
+        let err = syn::parse_str::<BindgenArgs>(r#"state_key = """#).err().unwrap();
+        assert_eq!(err.to_string(), "`state_key` should not be empty.");
+    }
+
+    #[test]
+    fn state_version() {
+        let args: BindgenArgs = parse_quote! { state_key = "CONFIG", state_version = 2 };
+        assert_eq!(args.state_version, Some(2));
+        let err = syn::parse_str::<BindgenArgs>("state_version = 1.5").err().unwrap();
+        assert_eq!(err.to_string(), "expected integer literal");
+    }
+
+    #[test]
+    fn legacy_init_argument() {
+        let args: BindgenArgs = parse_quote! { init => new };
+        assert!(args.serializer.is_none());
+        let args: BindgenArgs = parse_quote! { init => new, serializer = borsh };
+        assert!(args.default_serializer() == SerializerType::Borsh);
+    }
+
+    #[test]
+    fn unsupported_argument() {
+        let err = syn::parse_str::<BindgenArgs>("init = new").err().unwrap();
+        assert_eq!(err.to_string(), "Unsupported near_bindgen argument `init`.");
+        let err = syn::parse_str::<BindgenArgs>("serialiser = borsh").err().unwrap();
+        assert_eq!(err.to_string(), "Unsupported near_bindgen argument `serialiser`.");
+    }
+}
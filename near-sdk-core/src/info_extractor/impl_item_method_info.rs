@@ -1,4 +1,4 @@
-use crate::info_extractor::AttrSigInfo;
+use crate::info_extractor::{AttrSigInfo, SerializerType};
 use syn::{ImplItemMethod, Type, Visibility};
 
 /// Information extracted from `ImplItemMethod`.
@@ -9,17 +9,25 @@ pub struct ImplItemMethodInfo {
     pub is_public: bool,
     /// The type of the contract struct.
     pub struct_type: Type,
+    /// Whether JSON input can be a positional array of the arguments in addition to an object.
+    pub positional_args: bool,
 }
 
 impl ImplItemMethodInfo {
-    /// Process the method and extract information important for near-sdk.
-    pub fn new(original: &mut ImplItemMethod, struct_type: Type) -> syn::Result<Self> {
+    /// Process the method and extract information important for near-sdk. Arguments and the result
+    /// without a serializer attribute use `default_serializer`.
+    pub fn new(
+        original: &mut ImplItemMethod,
+        struct_type: Type,
+        default_serializer: SerializerType,
+        positional_args: bool,
+    ) -> syn::Result<Self> {
         let ImplItemMethod { attrs, sig, .. } = original;
-        let attr_signature_info = AttrSigInfo::new(attrs, sig)?;
+        let attr_signature_info = AttrSigInfo::new(attrs, sig, default_serializer)?;
         let is_public = match original.vis {
             Visibility::Public(_) => true,
             _ => false,
         };
-        Ok(Self { attr_signature_info, is_public, struct_type })
+        Ok(Self { attr_signature_info, is_public, struct_type, positional_args })
     }
 }
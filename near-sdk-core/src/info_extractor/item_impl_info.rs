@@ -1,35 +1,255 @@
+use crate::info_extractor::BindgenArgs;
 use crate::ImplItemMethodInfo;
+use quote::ToTokens;
+use std::collections::HashMap;
+use syn::fold::Fold;
 use syn::spanned::Spanned;
-use syn::{Error, ImplItem, ItemImpl, Type};
+use syn::{
+    parse_quote, AngleBracketedGenericArguments, Attribute, Error, GenericArgument, GenericParam,
+    Ident, ImplItem, ItemImpl, Meta, NestedMeta, PathArguments, Type,
+};
 
 /// Information extracted from `impl` section.
 pub struct ItemImplInfo {
     /// Whether this is a trait implementation.
     pub is_trait_impl: bool,
-    /// The type for which this `impl` is written.
+    /// The type for which this `impl` is written. For generic `impl` sections this is the concrete
+    /// type specified with `#[near_bindgen(instantiate = "...")]`.
     pub ty: Type,
     /// Info extracted for each method.
     pub methods: Vec<ImplItemMethodInfo>,
 }
 
 impl ItemImplInfo {
-    pub fn new(original: &mut ItemImpl) -> syn::Result<Self> {
-        if !original.generics.params.is_empty() {
+    pub fn new(original: &mut ItemImpl, args: &BindgenArgs) -> syn::Result<Self> {
+        if args.lazy_state || args.state_key.is_some() || args.state_version.is_some() {
             return Err(Error::new(
-                original.generics.params.span(),
-                "Impl type parameters are not supported for smart contracts.",
+                original.self_ty.span(),
+                "`lazy_state`, `state_key` and `state_version` can only be used on the contract \
+                 struct.",
             ));
         }
         let is_trait_impl = original.trait_.is_some();
-        let ty = (*original.self_ty.as_ref()).clone();
+        let mut substitution = TypeParamSubstitution::default();
+        let ty = match &args.instantiate {
+            Some(instantiate) => {
+                substitution = TypeParamSubstitution::new(original, instantiate)?;
+                instantiate.clone()
+            }
+            None if !original.generics.params.is_empty() => {
+                return Err(Error::new(
+                    original.generics.params.span(),
+                    "Impl type parameters are not supported for smart contracts. Use \
+                     `#[near_bindgen(instantiate = \"...\")]` to export the methods for a concrete type.",
+                ));
+            }
+            None => (*original.self_ty.as_ref()).clone(),
+        };
+        // The type is also used in expressions, e.g. `Contract::<T>::new()`.
+        let ty = Turbofish.fold_type(ty);
 
         let mut methods = vec![];
         for subitem in &mut original.items {
             if let ImplItem::Method(m) = subitem {
-                let method_info = ImplItemMethodInfo::new(m, ty.clone())?;
+                let mut method_info = ImplItemMethodInfo::new(
+                    m,
+                    ty.clone(),
+                    args.default_serializer(),
+                    args.positional_args.unwrap_or(true),
+                )?;
+                let attr_sig_info = &mut method_info.attr_signature_info;
+                for arg in &mut attr_sig_info.args {
+                    arg.ty = substitution.fold_type(arg.ty.clone());
+                }
+                attr_sig_info.returns =
+                    substitution.fold_return_type(attr_sig_info.returns.clone());
+                if attr_sig_info.has_view_attr && (method_info.is_public || is_trait_impl) {
+                    // Unit tests call the method directly rather than through the wrapper.
+                    m.block.stmts.insert(
+                        0,
+                        parse_quote! {
+                            #[cfg(any(debug_assertions, not(target_arch = "wasm32")))]
+                            let __near_view_guard = near_sdk::env::ViewGuard::enter();
+                        },
+                    );
+                }
                 methods.push(method_info);
             }
         }
-        Ok(Self { is_trait_impl, ty, methods })
+        let result = Self { is_trait_impl, ty, methods };
+        Self::check_exported_names(std::iter::once(&result))?;
+        Ok(result)
+    }
+
+    /// Checks that no two methods of the `impl` sections are exported under the same name, which
+    /// would otherwise result in a linker error.
+    pub fn check_exported_names<'a>(
+        infos: impl IntoIterator<Item = &'a ItemImplInfo>,
+    ) -> syn::Result<()> {
+        let mut exported: Vec<(&Ident, Vec<NestedMeta>)> = vec![];
+        for info in infos {
+            for method in &info.methods {
+                if !(method.is_public || info.is_trait_impl) {
+                    continue;
+                }
+                let attr_sig_info = &method.attr_signature_info;
+                let cfg = cfg_predicates(&attr_sig_info.non_bindgen_attrs);
+                let names =
+                    std::iter::once(&attr_sig_info.exported_name).chain(&attr_sig_info.aliases);
+                for name in names {
+                    // Methods compiled under mutually exclusive conditions can have the same name.
+                    let first = exported.iter().find(|(first, first_cfg)| {
+                        *first == name && !are_exclusive(first_cfg, &cfg)
+                    });
+                    if let Some((first, _)) = first {
+                        let mut err = Error::new(
+                            name.span(),
+                            format!("Method `{}` is exported more than once.", name),
+                        );
+                        err.combine(Error::new(
+                            first.span(),
+                            format!("Method `{}` is first exported here.", name),
+                        ));
+                        return Err(err);
+                    }
+                    exported.push((name, cfg.clone()));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The predicates of the `#[cfg]` attributes, that all hold if the item is compiled, with the
+/// `all(..)` predicates split into their parts.
+fn cfg_predicates(attrs: &[Attribute]) -> Vec<NestedMeta> {
+    fn split(predicate: NestedMeta, result: &mut Vec<NestedMeta>) {
+        match predicate {
+            NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("all") => {
+                list.nested.into_iter().for_each(|predicate| split(predicate, result))
+            }
+            predicate => result.push(predicate),
+        }
+    }
+    let mut result = vec![];
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("cfg")) {
+        if let Ok(Meta::List(list)) = attr.parse_meta() {
+            list.nested.into_iter().for_each(|predicate| split(predicate, &mut result));
+        }
+    }
+    result
+}
+
+/// Whether the items compiled under the predicates can not be compiled together, i.e. one of them
+/// requires `x` and the other `not(x)`. Other contradictions are not detected.
+fn are_exclusive(first: &[NestedMeta], second: &[NestedMeta]) -> bool {
+    let is_negation = |predicate: &NestedMeta, other: &NestedMeta| match predicate {
+        NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("not") => {
+            list.nested.len() == 1
+                && list.nested[0].to_token_stream().to_string()
+                    == other.to_token_stream().to_string()
+        }
+        _ => false,
+    };
+    first.iter().any(|first| {
+        second.iter().any(|second| is_negation(first, second) || is_negation(second, first))
+    })
+}
+
+/// Replaces the type parameters of the generic `impl` section with the concrete types.
+#[derive(Default)]
+struct TypeParamSubstitution {
+    types: HashMap<Ident, Type>,
+}
+
+impl TypeParamSubstitution {
+    /// Match the type of the `impl` section, e.g. `TokenContract<T>`, against its instantiation,
+    /// e.g. `TokenContract<DefaultPolicy>`.
+    fn new(original: &ItemImpl, instantiate: &Type) -> syn::Result<Self> {
+        let mismatch = || {
+            Error::new(
+                instantiate.span(),
+                format!(
+                    "`instantiate` type should be an instantiation of `{}`.",
+                    original.self_ty.to_token_stream().to_string().replace(' ', "")
+                ),
+            )
+        };
+        let generic_args = |ty: &Type| match ty {
+            Type::Path(p) if p.qself.is_none() => p.path.segments.last().map(|segment| {
+                let args = match &segment.arguments {
+                    PathArguments::AngleBracketed(args) => args.args.iter().cloned().collect(),
+                    _ => vec![],
+                };
+                (segment.ident.clone(), args)
+            }),
+            _ => None,
+        };
+        let (ident, params) = generic_args(&original.self_ty).ok_or_else(mismatch)?;
+        let (inst_ident, inst_args) = generic_args(instantiate).ok_or_else(mismatch)?;
+        let params: Vec<GenericArgument> =
+            params.into_iter().filter(|arg| !matches!(arg, GenericArgument::Lifetime(_))).collect();
+        let inst_args: Vec<GenericArgument> = inst_args
+            .into_iter()
+            .filter(|arg| !matches!(arg, GenericArgument::Lifetime(_)))
+            .collect();
+        if ident != inst_ident || params.len() != inst_args.len() {
+            return Err(mismatch());
+        }
+
+        let mut types = HashMap::new();
+        for (param, arg) in params.iter().zip(inst_args) {
+            if let (GenericArgument::Type(Type::Path(p)), GenericArgument::Type(arg)) = (param, arg)
+            {
+                if let Some(param_ident) = p.path.get_ident() {
+                    types.insert(param_ident.clone(), arg);
+                }
+            }
+        }
+        for param in &original.generics.params {
+            let ident = match param {
+                GenericParam::Type(t) => &t.ident,
+                GenericParam::Const(c) => &c.ident,
+                GenericParam::Lifetime(_) => continue,
+            };
+            if !types.contains_key(ident) {
+                return Err(Error::new(
+                    ident.span(),
+                    format!(
+                        "Type parameter `{}` is not determined by the `instantiate` type.",
+                        ident
+                    ),
+                ));
+            }
+        }
+        Ok(Self { types })
+    }
+}
+
+impl Fold for TypeParamSubstitution {
+    fn fold_type(&mut self, ty: Type) -> Type {
+        if let Type::Path(p) = &ty {
+            if let Some(concrete) = p.path.get_ident().and_then(|ident| self.types.get(ident)) {
+                if p.qself.is_none() {
+                    return concrete.clone();
+                }
+            }
+        }
+        syn::fold::fold_type(self, ty)
+    }
+}
+
+/// Adds `::` before the generic arguments of the paths, so that the type can be used in
+/// expressions.
+struct Turbofish;
+
+impl Fold for Turbofish {
+    fn fold_angle_bracketed_generic_arguments(
+        &mut self,
+        args: AngleBracketedGenericArguments,
+    ) -> AngleBracketedGenericArguments {
+        let mut args = syn::fold::fold_angle_bracketed_generic_arguments(self, args);
+        args.colon2_token = Some(Default::default());
+        args
     }
 }
@@ -0,0 +1,78 @@
+use crate::info_extractor::BindgenArgs;
+use syn::export::Span;
+use syn::spanned::Spanned;
+use syn::{Error, Generics, Ident, Index, ItemStruct, Member, Type, Visibility};
+
+/// Information extracted from the struct that represents the state of the contract.
+pub struct ItemStructInfo {
+    /// The name of the struct.
+    pub ident: Ident,
+    /// The visibility of the struct.
+    pub vis: Visibility,
+    /// The generics of the struct.
+    pub generics: Generics,
+    /// The fields of the struct, if each of them is stored under its own key with
+    /// `#[near_bindgen(lazy_state)]`.
+    pub lazy_fields: Option<Vec<Member>>,
+    /// The key under which the state is stored, set with `#[near_bindgen(state_key = "...")]`. The
+    /// fields of the lazy state are stored under `<state_key>.<field>` keys.
+    pub state_key: Option<String>,
+    /// The version of the state layout, set with `#[near_bindgen(state_version = N)]`.
+    pub state_version: Option<u32>,
+}
+
+impl ItemStructInfo {
+    pub fn new(original: &mut ItemStruct, args: &BindgenArgs) -> syn::Result<Self> {
+        if args.serializer.is_some() || args.instantiate.is_some() || args.positional_args.is_some()
+        {
+            return Err(Error::new(
+                Span::call_site(),
+                "Only `lazy_state`, `state_key` and `state_version` arguments can be used on the \
+                 contract struct.",
+            ));
+        }
+        let lazy_fields = if args.lazy_state {
+            // The state exists if its first field is stored.
+            if original.fields.is_empty() {
+                return Err(Error::new(
+                    original.ident.span(),
+                    "Contract with `lazy_state` should have fields.",
+                ));
+            }
+            let mut members = vec![];
+            for (i, field) in original.fields.iter().enumerate() {
+                if !is_lazy(&field.ty) {
+                    return Err(Error::new(
+                        field.ty.span(),
+                        "Fields of the contract with `lazy_state` should be `Lazy<T>`.",
+                    ));
+                }
+                members.push(match &field.ident {
+                    Some(ident) => Member::Named(ident.clone()),
+                    None => Member::Unnamed(Index::from(i)),
+                });
+            }
+            Some(members)
+        } else {
+            None
+        };
+        Ok(Self {
+            ident: original.ident.clone(),
+            vis: original.vis.clone(),
+            generics: original.generics.clone(),
+            lazy_fields,
+            state_key: args.state_key.as_ref().map(|key| key.value()),
+            state_version: args.state_version,
+        })
+    }
+}
+
+/// Whether the type is `Lazy<T>`.
+fn is_lazy(ty: &Type) -> bool {
+    match ty {
+        Type::Path(p) if p.qself.is_none() => {
+            p.path.segments.last().map(|segment| segment.ident == "Lazy").unwrap_or(false)
+        }
+        _ => false,
+    }
+}
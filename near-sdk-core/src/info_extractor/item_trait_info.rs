@@ -1,4 +1,4 @@
-use crate::info_extractor::TraitItemMethodInfo;
+use crate::info_extractor::{ExtContractArgs, TraitItemMethodInfo};
 use inflector::Inflector;
 use syn::export::Span;
 use syn::spanned::Spanned;
@@ -8,6 +8,8 @@ use syn::{Error, Ident, ItemTrait, TraitItem};
 pub struct ItemTraitInfo {
     /// The name of the module that will be used to generate the module.
     pub mod_name: Ident,
+    /// Whether `Client` is generated, set with `#[ext_contract(client)]`.
+    pub client: bool,
     /// Information extracted from the methods.
     pub methods: Vec<TraitItemMethodInfo>,
     /// The original AST.
@@ -15,8 +17,8 @@ pub struct ItemTraitInfo {
 }
 
 impl ItemTraitInfo {
-    pub fn new(original: &mut ItemTrait, mod_name_override: Option<Ident>) -> syn::Result<Self> {
-        let mod_name = mod_name_override.unwrap_or({
+    pub fn new(original: &mut ItemTrait, args: &ExtContractArgs) -> syn::Result<Self> {
+        let mod_name = args.mod_name.clone().unwrap_or({
             let res = original.ident.to_string().to_snake_case();
             Ident::new(&res, Span::call_site())
         });
@@ -31,7 +33,10 @@ impl ItemTraitInfo {
                     ))
                 }
                 TraitItem::Method(method) => {
-                    methods.push(TraitItemMethodInfo::new(method)?);
+                    methods.push(TraitItemMethodInfo::new(
+                        method,
+                        args.bindgen_args.default_serializer(),
+                    )?);
                     if method.default.is_some() {
                         return Err(Error::new(
                             method.span(),
@@ -44,6 +49,6 @@ impl ItemTraitInfo {
                 _ => {}
             }
         }
-        Ok(Self { original: original.clone(), mod_name, methods })
+        Ok(Self { original: original.clone(), mod_name, client: args.client, methods })
     }
 }
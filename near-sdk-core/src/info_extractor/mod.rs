@@ -1,6 +1,12 @@
 mod serializer_attr;
 pub use serializer_attr::SerializerAttr;
 
+mod validate_attr;
+pub use validate_attr::{ValidateAttr, Validator};
+
+mod bindgen_args;
+pub use bindgen_args::{BindgenArgs, ExtContractArgs, InitArgs, MethodBindgenArgs};
+
 mod arg_info;
 pub use arg_info::{ArgInfo, BindgenArgType};
 
@@ -19,9 +25,25 @@ pub use item_trait_info::ItemTraitInfo;
 mod item_impl_info;
 pub use item_impl_info::ItemImplInfo;
 
+mod item_struct_info;
+pub use item_struct_info::ItemStructInfo;
+
 /// Type of serialization we use.
-#[derive(PartialEq, Eq)]
+#[derive(PartialEq, Eq, Clone, Copy)]
 pub enum SerializerType {
     JSON,
     Borsh,
+    /// The argument or the result is `Vec<u8>` that is passed as is, without serialization.
+    Raw,
+    #[cfg(feature = "msgpack")]
+    MessagePack,
+    #[cfg(feature = "cbor")]
+    CBOR,
+}
+
+impl SerializerType {
+    /// Whether the values are serialized with serde, so that `#[serde(...)]` attributes apply.
+    pub fn is_serde(&self) -> bool {
+        !matches!(self, SerializerType::Borsh | SerializerType::Raw)
+    }
 }
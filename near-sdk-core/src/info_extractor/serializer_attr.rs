@@ -14,11 +14,31 @@ impl Parse for SerializerAttr {
         let content;
         let paren_token = parenthesized!(content in input);
         let ident: Ident = content.parse()?;
-        let serializer_type = match ident.to_string().as_str() {
-            "borsh" => SerializerType::Borsh,
-            "json" => SerializerType::JSON,
-            _ => return Err(Error::new(input.span(), "Unsupported serializer type.")),
-        };
+        let serializer_type = serializer_type(&ident)?;
         Ok(Self { paren_token, serializer_type })
     }
 }
+
+/// Get the serializer type from its name, e.g. `borsh`.
+pub fn serializer_type(ident: &Ident) -> syn::Result<SerializerType> {
+    match ident.to_string().as_str() {
+        "borsh" => Ok(SerializerType::Borsh),
+        "json" => Ok(SerializerType::JSON),
+        "raw" => Ok(SerializerType::Raw),
+        #[cfg(feature = "msgpack")]
+        "msgpack" => Ok(SerializerType::MessagePack),
+        #[cfg(not(feature = "msgpack"))]
+        "msgpack" => Err(Error::new(
+            ident.span(),
+            "MessagePack serializer requires the `msgpack` feature of near-sdk.",
+        )),
+        #[cfg(feature = "cbor")]
+        "cbor" => Ok(SerializerType::CBOR),
+        #[cfg(not(feature = "cbor"))]
+        "cbor" => Err(Error::new(
+            ident.span(),
+            "CBOR serializer requires the `cbor` feature of near-sdk.",
+        )),
+        _ => Err(Error::new(ident.span(), "Unsupported serializer type.")),
+    }
+}
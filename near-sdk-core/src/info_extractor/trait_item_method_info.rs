@@ -1,4 +1,4 @@
-use crate::info_extractor::AttrSigInfo;
+use crate::info_extractor::{AttrSigInfo, SerializerType};
 use syn::export::Span;
 use syn::spanned::Spanned;
 use syn::{Error, LitByteStr, TraitItemMethod};
@@ -9,12 +9,15 @@ pub struct TraitItemMethodInfo {
     pub attr_sig_info: AttrSigInfo,
     /// The original AST of the trait item method.
     pub original: TraitItemMethod,
-    /// Byte representation of method name, e.g. `b"my_method"`.
+    /// Byte representation of the exported method name, e.g. `b"my_method"`.
     pub ident_byte_str: LitByteStr,
 }
 
 impl TraitItemMethodInfo {
-    pub fn new(original: &mut TraitItemMethod) -> syn::Result<Self> {
+    pub fn new(
+        original: &mut TraitItemMethod,
+        default_serializer: SerializerType,
+    ) -> syn::Result<Self> {
         if original.default.is_some() {
             return Err(Error::new(
                 original.span(),
@@ -26,10 +29,10 @@ impl TraitItemMethodInfo {
 
         let TraitItemMethod { attrs, sig, .. } = original;
 
-        let attr_sig_info = AttrSigInfo::new(attrs, sig)?;
+        let attr_sig_info = AttrSigInfo::new(attrs, sig, default_serializer)?;
 
         let ident_byte_str =
-            LitByteStr::new(attr_sig_info.ident.to_string().as_bytes(), Span::call_site());
+            LitByteStr::new(attr_sig_info.exported_name.to_string().as_bytes(), Span::call_site());
 
         Ok(Self { attr_sig_info, original: original.clone(), ident_byte_str })
     }
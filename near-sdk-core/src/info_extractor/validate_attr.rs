@@ -0,0 +1,72 @@
+use proc_macro2::Ident;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{parenthesized, Error, Expr, Token};
+
+/// A check of the argument value that is done before the method is called.
+pub enum Validator {
+    /// `account_id`: the argument is a valid account id.
+    AccountId,
+    /// `range(min = MIN, max = MAX)`: the argument is within the inclusive range. Either of the
+    /// bounds can be omitted.
+    Range { min: Option<Box<Expr>>, max: Option<Box<Expr>> },
+    /// `max_len = MAX`: the length of the argument is at most `MAX`.
+    MaxLen(Box<Expr>),
+}
+
+/// Checks listed in `#[validate(...)]` attribute of the argument, e.g.
+/// `#[validate(range(min = 1), max_len = 10)]`.
+pub struct ValidateAttr {
+    pub validators: Vec<Validator>,
+}
+
+impl Parse for ValidateAttr {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let content;
+        parenthesized!(content in input);
+        let validators = Punctuated::<Validator, Token![,]>::parse_terminated(&content)?;
+        Ok(Self { validators: validators.into_iter().collect() })
+    }
+}
+
+impl Parse for Validator {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident: Ident = input.parse()?;
+        match ident.to_string().as_str() {
+            "account_id" => Ok(Validator::AccountId),
+            "range" => {
+                let content;
+                parenthesized!(content in input);
+                let mut min = None;
+                let mut max = None;
+                while !content.is_empty() {
+                    let bound: Ident = content.parse()?;
+                    content.parse::<Token![=]>()?;
+                    let value: Box<Expr> = content.parse()?;
+                    match bound.to_string().as_str() {
+                        "min" if min.is_none() => min = Some(value),
+                        "max" if max.is_none() => max = Some(value),
+                        _ => {
+                            return Err(Error::new(
+                                bound.span(),
+                                "Expected `min = ...` and/or `max = ...` in `range`.",
+                            ))
+                        }
+                    }
+                    if !content.is_empty() {
+                        content.parse::<Token![,]>()?;
+                    }
+                }
+                if min.is_none() && max.is_none() {
+                    return Err(Error::new(ident.span(), "Range should specify `min` or `max`."));
+                }
+                Ok(Validator::Range { min, max })
+            }
+            "max_len" => {
+                input.parse::<Token![=]>()?;
+                Ok(Validator::MaxLen(input.parse()?))
+            }
+            _ => Err(Error::new(ident.span(), "Unsupported validation.")),
+        }
+    }
+}
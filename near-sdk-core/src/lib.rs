@@ -3,6 +3,6 @@
 mod code_generator;
 mod info_extractor;
 mod metadata;
-pub use code_generator::*;
 pub use info_extractor::*;
+pub use metadata::metadata_generator::type_name;
 pub use metadata::metadata_visitor::MetadataVisitor;
@@ -1,8 +1,9 @@
-use crate::{BindgenArgType, ImplItemMethodInfo, SerializerType};
+use crate::code_generator::owned_type::owned_type;
+use crate::{ArgInfo, BindgenArgType, ImplItemMethodInfo, SerializerType};
 
-use quote::quote;
+use quote::{quote, ToTokens};
 use syn::export::TokenStream2;
-use syn::ReturnType;
+use syn::{parse_quote, Lit, Meta, MetaNameValue, ReturnType, Type};
 
 impl ImplItemMethodInfo {
     /// Generates metadata struct for this method.
@@ -16,8 +17,13 @@ impl ImplItemMethodInfo {
     /// ```ignore
     /// near_sdk::MethodMetadata {
     ///     name: "f3".to_string(),
+    ///     docs: None,
     ///     is_view: false,
     ///     is_init: false,
+    ///     is_payable: false,
+    ///     access: near_sdk::AccessRestriction::Public,
+    ///     input_serializer: near_sdk::abi::SerializerType::JSON,
+    ///     positional_args: true,
     ///     args: {
     ///         #[derive(borsh::BorshSchema)]
     ///         #[derive(serde :: Deserialize, serde :: Serialize)]
@@ -29,23 +35,44 @@ impl ImplItemMethodInfo {
     ///     },
     ///     callbacks: vec![],
     ///     callbacks_vec: None,
+    ///     result_serializer: near_sdk::abi::SerializerType::JSON,
     ///     result: Some(Result < IsOk, Error > ::schema_container())
     /// }
     /// ```
     /// If args are serialized with Borsh it will not include `#[derive(borsh::BorshSchema)]`.
     pub fn metadata_struct(&self) -> TokenStream2 {
-        let method_name_str = self.attr_signature_info.ident.to_string();
-        let is_view = match &self.attr_signature_info.receiver {
-            None => true,
-            Some(rec) => rec.mutability.is_none(),
+        let method_name_str = self.attr_signature_info.exported_name.to_string();
+        let docs = match self.docs() {
+            Some(docs) => quote! { Some(#docs.to_string()) },
+            None => quote! { None },
         };
+        let is_view = self.attr_signature_info.is_view;
         let is_init = self.attr_signature_info.is_init;
+        let is_payable = self.attr_signature_info.is_payable;
+        // Migrations check that they are called by the contract itself.
+        let access = match self.attr_signature_info.migrate_from {
+            Some(_) => quote! { near_sdk::AccessRestriction::SelfOnly },
+            None => quote! { near_sdk::AccessRestriction::Public },
+        };
+        let input_serializer = self.attr_signature_info.input_serializer.abi_variant();
+        let positional_args = self.positional_args;
+        let result_serializer = self.attr_signature_info.result_serializer.abi_variant();
+        let callback_metadata = |arg: &ArgInfo| {
+            let ty = &arg.ty;
+            let serializer = arg.serializer_ty.abi_variant();
+            quote! {
+                near_sdk::CallbackMetadata {
+                    serializer: #serializer,
+                    schema: <#ty>::schema_container()
+                }
+            }
+        };
         let args = if self.attr_signature_info.input_args().next().is_some() {
             let input_struct = self.attr_signature_info.input_struct();
-            // If input args are JSON then we need to additionally specify schema for them.
+            // If input args are not Borsh then we need to additionally specify schema for them.
             let additional_schema = match &self.attr_signature_info.input_serializer {
                 SerializerType::Borsh => TokenStream2::new(),
-                SerializerType::JSON => quote! {
+                _ => quote! {
                     #[derive(borsh::BorshSchema)]
                 },
             };
@@ -69,12 +96,7 @@ impl ImplItemMethodInfo {
                 BindgenArgType::CallbackArg => true,
                 _ => false,
             })
-            .map(|arg| {
-                let ty = &arg.ty;
-                quote! {
-                    #ty::schema_container()
-                }
-            })
+            .map(callback_metadata)
             .collect();
         let callbacks_vec = match self
             .attr_signature_info
@@ -92,9 +114,9 @@ impl ImplItemMethodInfo {
                 }
             }
             Some(arg) => {
-                let ty = &arg.ty;
+                let callback_metadata = callback_metadata(arg);
                 quote! {
-                    Some(#ty::schema_container())
+                    Some(#callback_metadata)
                 }
             }
         };
@@ -114,13 +136,169 @@ impl ImplItemMethodInfo {
         quote! {
              near_sdk::MethodMetadata {
                  name: #method_name_str.to_string(),
+                 docs: #docs,
                  is_view: #is_view,
                  is_init: #is_init,
+                 is_payable: #is_payable,
+                 access: #access,
+                 input_serializer: #input_serializer,
+                 positional_args: #positional_args,
                  args: #args,
                  callbacks: vec![#(#callbacks),*],
                  callbacks_vec: #callbacks_vec,
+                 result_serializer: #result_serializer,
                  result: #result
              }
         }
     }
+
+    /// Generates the ABI of this method.
+    ///
+    /// # Example:
+    /// The following method:
+    /// ```ignore
+    /// #[payable]
+    /// pub fn f2(&mut self, arg0: &FancyStruct) -> u64 { }
+    /// ```
+    /// will produce this struct:
+    /// ```ignore
+    /// near_sdk::abi::MethodAbi {
+    ///     name: "f2".to_string(),
+    ///     is_view: false,
+    ///     is_init: false,
+    ///     is_payable: true,
+    ///     positional_args: true,
+    ///     args: vec![
+    ///         near_sdk::abi::ArgAbi::new::<FancyStruct>("arg0", "FancyStruct", near_sdk::abi::SerializerType::JSON)
+    ///     ],
+    ///     callbacks: vec![],
+    ///     callbacks_vec: None,
+    ///     result: Some(near_sdk::abi::TypeAbi::new::<u64>("u64", near_sdk::abi::SerializerType::JSON))
+    /// }
+    /// ```
+    pub fn abi_struct(&self) -> TokenStream2 {
+        let attr_sig_info = &self.attr_signature_info;
+        let name = attr_sig_info.exported_name.to_string();
+        let is_view = attr_sig_info.is_view;
+        let is_init = attr_sig_info.is_init;
+        let is_payable = attr_sig_info.is_payable;
+        let positional_args = self.positional_args;
+        let arg_abi = |arg: &ArgInfo| {
+            let ty = self.abi_arg_type(arg);
+            let name = arg.ident.to_string();
+            let type_name = type_name(&ty);
+            let serializer = arg.serializer_ty.abi_variant();
+            quote! {
+                near_sdk::abi::ArgAbi::new::<#ty>(#name, #type_name, #serializer)
+            }
+        };
+        let args: Vec<_> = attr_sig_info.input_args().map(arg_abi).collect();
+        let callbacks: Vec<_> = attr_sig_info
+            .args
+            .iter()
+            .filter(|arg| arg.bindgen_ty == BindgenArgType::CallbackArg)
+            .map(arg_abi)
+            .collect();
+        let callbacks_vec = match attr_sig_info
+            .args
+            .iter()
+            .rev()
+            .find(|arg| arg.bindgen_ty == BindgenArgType::CallbackArgVec)
+        {
+            None => quote! { None },
+            Some(arg) => {
+                let arg_abi = arg_abi(arg);
+                quote! { Some(#arg_abi) }
+            }
+        };
+        let result = match self.abi_result_type() {
+            Some(ty) => {
+                let type_name = type_name(&ty);
+                let serializer = attr_sig_info.result_serializer.abi_variant();
+                quote! {
+                    Some(near_sdk::abi::TypeAbi::new::<#ty>(#type_name, #serializer))
+                }
+            }
+            _ => quote! { None },
+        };
+        quote! {
+            near_sdk::abi::MethodAbi {
+                name: #name.to_string(),
+                is_view: #is_view,
+                is_init: #is_init,
+                is_payable: #is_payable,
+                positional_args: #positional_args,
+                args: vec![#(#args),*],
+                callbacks: vec![#(#callbacks),*],
+                callbacks_vec: #callbacks_vec,
+                result: #result
+            }
+        }
+    }
+
+    /// The doc comments of the method joined into lines, without the leading space of each line.
+    fn docs(&self) -> Option<String> {
+        let lines: Vec<String> = self
+            .attr_signature_info
+            .non_bindgen_attrs
+            .iter()
+            .filter_map(|attr| match attr.parse_meta() {
+                Ok(Meta::NameValue(MetaNameValue { path, lit: Lit::Str(lit), .. }))
+                    if path.is_ident("doc") =>
+                {
+                    let line = lit.value();
+                    Some(line.strip_prefix(' ').unwrap_or(&line).to_string())
+                }
+                _ => None,
+            })
+            .collect();
+        if lines.is_empty() {
+            None
+        } else {
+            Some(lines.join("\n"))
+        }
+    }
+
+    /// The type of the argument as it is deserialized, e.g. `String` for `&str`.
+    pub fn abi_arg_type(&self, arg: &ArgInfo) -> Type {
+        let ty = &arg.ty;
+        let ty: Type = match arg.reference {
+            Some(_) => parse_quote! { &#ty },
+            None => ty.clone(),
+        };
+        owned_type(&ty, Some(&self.struct_type))
+    }
+
+    /// The type of the returned value, with `Self` replaced by the type of the contract. `None` if
+    /// the method returns nothing or is an init method, which writes the state instead.
+    pub fn abi_result_type(&self) -> Option<Type> {
+        match &self.attr_signature_info.returns {
+            ReturnType::Type(_, ty) if !self.attr_signature_info.is_init => {
+                Some(owned_type(ty, Some(&self.struct_type)))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The type as it is written in Rust, e.g. `Vec<(u64, String)>`.
+pub fn type_name(ty: &Type) -> String {
+    let tokens = ty.to_token_stream().to_string();
+    let chars: Vec<char> = tokens.chars().collect();
+    let mut result = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        if *c == ' ' {
+            // Spaces are only needed between words, e.g. in `dyn Trait`, and after commas.
+            let is_word = |c: Option<&char>| c.map(|c| c.is_alphanumeric() || *c == '_');
+            if is_word(chars.get(i.wrapping_sub(1))) == Some(true)
+                && is_word(chars.get(i + 1)) == Some(true)
+                || chars.get(i.wrapping_sub(1)) == Some(&',')
+            {
+                result.push(' ');
+            }
+        } else {
+            result.push(*c);
+        }
+    }
+    result
 }
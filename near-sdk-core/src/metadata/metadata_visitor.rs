@@ -2,12 +2,12 @@
 //! it decorates. Note, that this in an inner attribute. For it to work we should be
 //! able to visit every method in the module intended to be a contract method.
 //! For this we implement the visitor.
-use crate::ItemImplInfo;
+use crate::{BindgenArgs, ImplItemMethodInfo, ItemImplInfo};
 
 use quote::quote;
 use syn::export::{ToTokens, TokenStream2};
 use syn::visit::Visit;
-use syn::{Error, ItemImpl};
+use syn::{Error, File, ItemImpl, ItemMacro};
 
 /// Information relevant to metadata extracted from the `impl` section decorated with `#[near_bindgen]`.
 #[derive(Default)]
@@ -19,18 +19,34 @@ pub struct MetadataVisitor {
 
 impl<'ast> Visit<'ast> for MetadataVisitor {
     fn visit_item_impl(&mut self, i: &'ast ItemImpl) {
-        let has_near_sdk_attr = i
+        let near_sdk_attr = i
             .attrs
             .iter()
-            .any(|attr| attr.path.to_token_stream().to_string().as_str() == "near_bindgen");
-        if has_near_sdk_attr {
-            match ItemImplInfo::new(&mut i.clone()) {
+            .find(|attr| attr.path.to_token_stream().to_string().as_str() == "near_bindgen");
+        if let Some(attr) = near_sdk_attr {
+            let args = if attr.tokens.is_empty() {
+                Ok(BindgenArgs::default())
+            } else {
+                attr.parse_args::<BindgenArgs>()
+            };
+            match args.and_then(|args| ItemImplInfo::new(&mut i.clone(), &args)) {
                 Ok(info) => self.impl_item_infos.push(info),
                 Err(err) => self.errors.push(err),
             }
         }
         syn::visit::visit_item_impl(self, i);
     }
+
+    /// Visits the code wrapped into `metadata!{}`, when the source of the contract is read as is.
+    fn visit_item_macro(&mut self, i: &'ast ItemMacro) {
+        if i.mac.path.is_ident("metadata") {
+            match syn::parse2::<File>(i.mac.tokens.clone()) {
+                Ok(file) => self.visit_file(&file),
+                Err(err) => self.errors.push(err),
+            }
+        }
+        syn::visit::visit_item_macro(self, i);
+    }
 }
 
 impl MetadataVisitor {
@@ -42,6 +58,7 @@ impl MetadataVisitor {
         if !self.errors.is_empty() {
             return Err(self.errors[0].clone());
         }
+        ItemImplInfo::check_exported_names(&self.impl_item_infos)?;
         let panic_hook = quote! {
             near_sdk::env::setup_panic_hook();
         };
@@ -69,6 +86,48 @@ impl MetadataVisitor {
             }
         })
     }
+
+    /// The methods that are exported by the contract, i.e. public methods and methods of the trait
+    /// implementations. Returns the first error that occurred while visiting the code, if any.
+    pub fn exported_methods(&self) -> syn::Result<Vec<&ImplItemMethodInfo>> {
+        if !self.errors.is_empty() {
+            return Err(self.errors[0].clone());
+        }
+        ItemImplInfo::check_exported_names(&self.impl_item_infos)?;
+        Ok(self
+            .impl_item_infos
+            .iter()
+            .flat_map(|i| i.methods.iter().filter(move |m| m.is_public || i.is_trait_impl))
+            .collect())
+    }
+
+    /// Generate `contract_abi` method that returns the JSON ABI of the exported methods. When the
+    /// contract is compiled natively `contract_abi()` returns the ABI instead, e.g. to write it into
+    /// a file from a test.
+    pub fn generate_abi_method(&self) -> syn::Result<TokenStream2> {
+        let methods: Vec<TokenStream2> =
+            self.exported_methods()?.into_iter().map(|m| m.abi_struct()).collect();
+        Ok(quote! {
+            /// The JSON ABI of the contract.
+            #[cfg(not(target_arch = "wasm32"))]
+            pub fn contract_abi() -> near_sdk::abi::ContractAbi {
+                near_sdk::abi::ContractAbi::new(vec![
+                    #(#methods),*
+                ])
+            }
+
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn contract_abi() {
+                near_sdk::env::setup_panic_hook();
+                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+                let abi = near_sdk::abi::ContractAbi::new(vec![
+                    #(#methods),*
+                ]);
+                near_sdk::env::value_return(abi.to_json().as_bytes());
+            }
+        })
+    }
 }
 
 #[rustfmt::skip]
@@ -82,12 +141,19 @@ mod tests {
         let code = quote! {
             #[near_bindgen]
             impl Hello {
+                /// Does nothing.
+                ///
+                /// Really.
                 pub fn f1(&self) { }
+                #[payable]
                 pub fn f2(&mut self, arg0: FancyStruct, arg1: u64) { }
+                #[migrate]
+                pub fn f4(old: OldHello, #[callback] #[serializer(borsh)] value: u64, #[callback_vec] values: Vec<u8>) -> Self { }
             }
 
             #[near_bindgen]
             impl SomeTrait for Hello {
+                #[result_serializer(borsh)]
                 fn f3(&mut self, arg0: FancyStruct, arg1: u64) -> Result<IsOk, Error> { }
             }
         };
@@ -108,17 +174,28 @@ mod tests {
                 let metadata = near_sdk::Metadata::new(vec![
                     near_sdk::MethodMetadata {
                         name: "f1".to_string(),
+                        docs: Some("Does nothing.\n\nReally.".to_string()),
                         is_view: true,
                         is_init: false,
+                        is_payable: false,
+                        access: near_sdk::AccessRestriction::Public,
+                        input_serializer: near_sdk::abi::SerializerType::JSON,
+                        positional_args: true,
                         args: None,
                         callbacks: vec![],
                         callbacks_vec: None,
+                        result_serializer: near_sdk::abi::SerializerType::JSON,
                         result: None
                     },
                     near_sdk::MethodMetadata {
                         name: "f2".to_string(),
+                        docs: None,
                         is_view: false,
                         is_init: false,
+                        is_payable: true,
+                        access: near_sdk::AccessRestriction::Public,
+                        input_serializer: near_sdk::abi::SerializerType::JSON,
+                        positional_args: true,
                         args: {
                             #[derive(borsh::BorshSchema)]
                             #[derive(serde :: Deserialize, serde :: Serialize)]
@@ -130,12 +207,39 @@ mod tests {
                         },
                         callbacks: vec![],
                         callbacks_vec: None,
+                        result_serializer: near_sdk::abi::SerializerType::JSON,
                         result: None
                     },
+                    near_sdk::MethodMetadata {
+                        name: "f4".to_string(),
+                        docs: None,
+                        is_view: false,
+                        is_init: true,
+                        is_payable: false,
+                        access: near_sdk::AccessRestriction::SelfOnly,
+                        input_serializer: near_sdk::abi::SerializerType::JSON,
+                        positional_args: true,
+                        args: None,
+                        callbacks: vec![near_sdk::CallbackMetadata {
+                            serializer: near_sdk::abi::SerializerType::Borsh,
+                            schema: <u64>::schema_container()
+                        }],
+                        callbacks_vec: Some(near_sdk::CallbackMetadata {
+                            serializer: near_sdk::abi::SerializerType::JSON,
+                            schema: <Vec<u8> >::schema_container()
+                        }),
+                        result_serializer: near_sdk::abi::SerializerType::JSON,
+                        result: Some(Self::schema_container())
+                    },
                     near_sdk::MethodMetadata {
                         name: "f3".to_string(),
+                        docs: None,
                         is_view: false,
                         is_init: false,
+                        is_payable: false,
+                        access: near_sdk::AccessRestriction::Public,
+                        input_serializer: near_sdk::abi::SerializerType::JSON,
+                        positional_args: true,
                         args: {
                             #[derive(borsh::BorshSchema)]
                             #[derive(serde :: Deserialize, serde :: Serialize)]
@@ -147,6 +251,7 @@ mod tests {
                         },
                         callbacks: vec![],
                         callbacks_vec: None,
+                        result_serializer: near_sdk::abi::SerializerType::Borsh,
                         result: Some(Result < IsOk, Error > ::schema_container())
                     }
                 ]);
@@ -157,4 +262,126 @@ mod tests {
         );
         assert_eq!(expected.to_string(), actual.to_string());
     }
+
+    #[test]
+    fn abi() {
+        let code = quote! {
+            #[near_bindgen]
+            impl Hello {
+                #[init]
+                pub fn new(owner_id: &str) -> Self { }
+                pub fn f1(&self) -> Vec<&Self> { }
+                #[payable]
+                pub fn f2(&mut self, #[serializer(borsh)] arg0: FancyStruct, #[callback] result: U128) { }
+                fn private(&self) { }
+            }
+
+            #[near_bindgen]
+            impl SomeTrait for Hello {
+                #[result_serializer(borsh)]
+                fn f3(&self, #[callback_vec] results: Vec<u64>) -> Option<(u64, String)> { }
+            }
+        };
+
+        let file: syn::File = syn::parse2(code).unwrap();
+
+        let mut visitor = MetadataVisitor::new();
+        visitor.visit_file(&file);
+
+        let actual = visitor.generate_abi_method().unwrap();
+        let methods = quote! {
+            near_sdk::abi::MethodAbi {
+                name: "new".to_string(),
+                is_view: false,
+                is_init: true,
+                is_payable: false,
+                positional_args: true,
+                args: vec![near_sdk::abi::ArgAbi::new::<String>("owner_id", "String", near_sdk::abi::SerializerType::JSON)],
+                callbacks: vec![],
+                callbacks_vec: None,
+                result: None
+            },
+            near_sdk::abi::MethodAbi {
+                name: "f1".to_string(),
+                is_view: true,
+                is_init: false,
+                is_payable: false,
+                positional_args: true,
+                args: vec![],
+                callbacks: vec![],
+                callbacks_vec: None,
+                result: Some(near_sdk::abi::TypeAbi::new::<Vec<Hello> >("Vec<Hello>", near_sdk::abi::SerializerType::JSON))
+            },
+            near_sdk::abi::MethodAbi {
+                name: "f2".to_string(),
+                is_view: false,
+                is_init: false,
+                is_payable: true,
+                positional_args: true,
+                args: vec![near_sdk::abi::ArgAbi::new::<FancyStruct>("arg0", "FancyStruct", near_sdk::abi::SerializerType::Borsh)],
+                callbacks: vec![near_sdk::abi::ArgAbi::new::<U128>("result", "U128", near_sdk::abi::SerializerType::JSON)],
+                callbacks_vec: None,
+                result: None
+            },
+            near_sdk::abi::MethodAbi {
+                name: "f3".to_string(),
+                is_view: true,
+                is_init: false,
+                is_payable: false,
+                positional_args: true,
+                args: vec![],
+                callbacks: vec![],
+                callbacks_vec: Some(near_sdk::abi::ArgAbi::new::<Vec<u64> >("results", "Vec<u64>", near_sdk::abi::SerializerType::JSON)),
+                result: Some(near_sdk::abi::TypeAbi::new::<Option<(u64, String)> >("Option<(u64, String)>", near_sdk::abi::SerializerType::Borsh))
+            }
+        };
+        let expected = quote!(
+            /// The JSON ABI of the contract.
+            #[cfg(not(target_arch = "wasm32"))]
+            pub fn contract_abi() -> near_sdk::abi::ContractAbi {
+                near_sdk::abi::ContractAbi::new(vec![
+                    #methods
+                ])
+            }
+
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn contract_abi() {
+                near_sdk::env::setup_panic_hook();
+                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+                let abi = near_sdk::abi::ContractAbi::new(vec![
+                    #methods
+                ]);
+                near_sdk::env::value_return(abi.to_json().as_bytes());
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn duplicate_exported_names() {
+        let code = quote! {
+            #[near_bindgen]
+            impl Hello {
+                pub fn f1(&self) { }
+            }
+
+            #[near_bindgen]
+            impl SomeTrait for Hello {
+                fn f1(&self) { }
+            }
+        };
+
+        let file: syn::File = syn::parse2(code).unwrap();
+
+        let mut visitor = MetadataVisitor::new();
+        visitor.visit_file(&file);
+
+        let err = visitor.generate_metadata_method().err().unwrap();
+        let messages: Vec<String> = err.into_iter().map(|err| err.to_string()).collect();
+        assert_eq!(
+            messages,
+            vec!["Method `f1` is exported more than once.", "Method `f1` is first exported here."]
+        );
+    }
 }
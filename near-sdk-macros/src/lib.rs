@@ -10,26 +10,50 @@ use syn::visit::Visit;
 use syn::{File, ItemImpl, ItemStruct, ItemTrait};
 
 #[proc_macro_attribute]
-pub fn near_bindgen(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    if let Ok(input) = syn::parse::<ItemStruct>(item.clone()) {
+pub fn near_bindgen(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if let Ok(mut input) = syn::parse::<ItemStruct>(item.clone()) {
+        let args = match syn::parse::<BindgenArgs>(attr) {
+            Ok(x) => x,
+            Err(err) => {
+                return err.to_compile_error().into();
+            }
+        };
+        let item_struct_info = match ItemStructInfo::new(&mut input, &args) {
+            Ok(x) => x,
+            Err(err) => {
+                return err.to_compile_error().into();
+            }
+        };
+        let ext_self_struct = item_struct_info.ext_self_struct();
+        let contract_state_impl = item_struct_info.contract_state_impl();
         let sys_file = rust_file(include_bytes!("../res/sys.rs"));
         let near_environment = rust_file(include_bytes!("../res/near_blockchain.rs"));
         return TokenStream::from(quote! {
             #input
+            #ext_self_struct
+            #contract_state_impl
             #sys_file
             #near_environment
         });
     } else if let Ok(mut input) = syn::parse::<ItemImpl>(item) {
-        let item_impl_info = match ItemImplInfo::new(&mut input) {
+        let args = match syn::parse::<BindgenArgs>(attr) {
+            Ok(x) => x,
+            Err(err) => {
+                return err.to_compile_error().into();
+            }
+        };
+        let item_impl_info = match ItemImplInfo::new(&mut input, &args) {
             Ok(x) => x,
             Err(err) => {
                 return err.to_compile_error().into();
             }
         };
         let generated_code = item_impl_info.wrapper_code();
+        let ext_self_code = item_impl_info.ext_self_code();
         TokenStream::from(quote! {
             #input
             #generated_code
+            #ext_self_code
         })
     } else {
         TokenStream::from(
@@ -50,22 +74,19 @@ fn rust_file(data: &[u8]) -> File {
 #[proc_macro_attribute]
 pub fn ext_contract(attr: TokenStream, item: TokenStream) -> TokenStream {
     if let Ok(mut input) = syn::parse::<ItemTrait>(item.clone()) {
-        let mut mod_name: Option<proc_macro2::Ident> = None;
-        if !attr.is_empty() {
-            mod_name = match syn::parse(attr) {
-                Ok(x) => x,
-                Err(err) => {
-                    return TokenStream::from(
-                        syn::Error::new(
-                            Span::call_site(),
-                            format!("Failed to parse mod name for ext_contract: {}", err),
-                        )
-                        .to_compile_error(),
+        let args: ExtContractArgs = match syn::parse(attr) {
+            Ok(x) => x,
+            Err(err) => {
+                return TokenStream::from(
+                    syn::Error::new(
+                        err.span(),
+                        format!("Failed to parse arguments for ext_contract: {}", err),
                     )
-                }
-            };
-        }
-        let item_trait_info = match ItemTraitInfo::new(&mut input, mod_name) {
+                    .to_compile_error(),
+                )
+            }
+        };
+        let item_trait_info = match ItemTraitInfo::new(&mut input, &args) {
             Ok(x) => x,
             Err(err) => return TokenStream::from(err.to_compile_error()),
         };
@@ -110,7 +131,7 @@ pub fn init(_attr: TokenStream, item: TokenStream) -> TokenStream {
     item
 }
 
-/// `metadata` generates the metadata method and should be placed at the very end of the `lib.rs` file.
+/// `metadata` generates the metadata and the JSON ABI methods and should be placed at the very end of the `lib.rs` file.
 /// TODO: Once Rust allows inner attributes and custom procedural macros for modules we should switch this
 /// to be `#![metadata]` attribute at the top of the contract file instead. https://github.com/rust-lang/rust/issues/54727
 #[proc_macro]
@@ -122,9 +143,14 @@ pub fn metadata(item: TokenStream) -> TokenStream {
             Ok(x) => x,
             Err(err) => return TokenStream::from(err.to_compile_error()),
         };
+        let abi = match visitor.generate_abi_method() {
+            Ok(x) => x,
+            Err(err) => return TokenStream::from(err.to_compile_error()),
+        };
         TokenStream::from(quote! {
             #input
             #generated
+            #abi
         })
     } else {
         TokenStream::from(
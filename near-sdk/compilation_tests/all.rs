@@ -8,10 +8,32 @@ fn tests() {
     t.compile_fail("compilation_tests/metadata_invalid_rust.rs");
     t.pass("compilation_tests/complex.rs");
     t.compile_fail("compilation_tests/impl_generic.rs");
+    t.pass("compilation_tests/impl_instantiate.rs");
     t.compile_fail("compilation_tests/bad_argument.rs");
     t.pass("compilation_tests/references.rs");
     t.pass("compilation_tests/init_function.rs");
     t.pass("compilation_tests/lifetime_method.rs");
     t.pass("compilation_tests/cond_compilation.rs");
+    t.pass("compilation_tests/impl_serializer.rs");
+    t.pass("compilation_tests/raw_serializer.rs");
+    t.pass("compilation_tests/default_args.rs");
+    t.pass("compilation_tests/positional_args.rs");
+    t.pass("compilation_tests/validate_args.rs");
     t.compile_fail("compilation_tests/payable_view.rs");
+    t.pass("compilation_tests/view_call.rs");
+    t.compile_fail("compilation_tests/view_call_conflict.rs");
+    t.pass("compilation_tests/rename_alias.rs");
+    t.compile_fail("compilation_tests/duplicate_names.rs");
+    t.pass("compilation_tests/lazy_state.rs");
+    t.compile_fail("compilation_tests/lazy_state_field.rs");
+    t.pass("compilation_tests/state_key.rs");
+    t.pass("compilation_tests/migrate.rs");
+    t.pass("compilation_tests/upgrade.rs");
+    t.pass("compilation_tests/blob_staging.rs");
+    t.pass("compilation_tests/contract_abi.rs");
+    t.pass("compilation_tests/ext_client.rs");
+    t.pass("compilation_tests/ext_self_path.rs");
+    t.pass("compilation_tests/ext_contract_promise.rs");
+    t.pass("compilation_tests/typed_callbacks.rs");
+    t.compile_fail("compilation_tests/typed_callback_mismatch.rs");
 }
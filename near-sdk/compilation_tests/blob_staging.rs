@@ -0,0 +1,45 @@
+//! Contract that stages its new code in chunks before upgrading to it.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::collections::BlobStaging;
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::upgrade::Upgrade;
+use near_sdk::{near_bindgen, AccountId, Promise};
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize)]
+struct Factory {
+    owner_id: AccountId,
+    code: BlobStaging,
+}
+
+impl Default for Factory {
+    fn default() -> Self {
+        Self::new(AccountId::new())
+    }
+}
+
+#[near_bindgen]
+impl Factory {
+    #[init]
+    pub fn new(owner_id: AccountId) -> Self {
+        Self { code: BlobStaging::new(b"c".to_vec(), 10u128.pow(19), owner_id.clone()), owner_id }
+    }
+
+    #[payable]
+    pub fn stage_code(&mut self, index: u64, chunk: Base64VecU8) {
+        self.code.add_chunk(index, &chunk.0);
+    }
+
+    #[payable]
+    pub fn stage_raw_code(&mut self, #[serializer(raw)] chunk: Vec<u8>) -> u64 {
+        self.code.push_chunk(&chunk)
+    }
+
+    pub fn upgrade(&mut self, sha256: Base64VecU8) -> Promise {
+        let code = self.code.take(&sha256.0);
+        Upgrade::new(code).deploy(|account_id| account_id == &self.owner_id)
+    }
+}
+
+fn main() {}
@@ -0,0 +1,40 @@
+//! Contract with JSON ABI generated by `metadata!{}`.
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::{metadata, near_bindgen};
+use serde::{Deserialize, Serialize};
+
+metadata! {
+#[derive(Serialize, Deserialize, BorshSchema)]
+pub struct Transfer {
+    receiver_id: String,
+    amount: U128,
+    memo: Option<String>,
+}
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Bank {
+    total: u128,
+}
+
+#[near_bindgen]
+impl Bank {
+    #[payable]
+    pub fn transfer(&mut self, transfer: Transfer) -> U128 {
+        self.total += transfer.amount.0;
+        self.total.into()
+    }
+
+    pub fn total(&self) -> U128 {
+        self.total.into()
+    }
+}
+}
+
+fn main() {
+    let abi = contract_abi();
+    assert_eq!(abi.methods.len(), 2);
+    assert!(abi.to_json().contains("\"receiver_id\""));
+}
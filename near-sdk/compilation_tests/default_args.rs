@@ -0,0 +1,25 @@
+//! Smart contract with arguments that can be omitted from the input.
+
+use near_sdk::{ext_contract, near_bindgen};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Storage {
+    values: Vec<u64>,
+}
+
+#[ext_contract]
+pub trait ExtStorage {
+    fn get_values(&self, #[serde(rename = "from")] from_index: Option<u64>, #[default(10)] limit: u64) -> Vec<u64>;
+}
+
+#[near_bindgen(positional_args = false)]
+impl Storage {
+    pub fn get_values(&self, #[serde(rename = "from")] from_index: Option<u64>, #[default(10)] limit: u64) -> Vec<u64> {
+        let from_index = from_index.unwrap_or(0);
+        self.values.iter().skip(from_index as usize).take(limit as usize).cloned().collect()
+    }
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+//! Methods cannot be exported under the same name.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::near_bindgen;
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Token {}
+
+#[near_bindgen]
+impl Token {
+    pub fn total_supply(&self) {}
+
+    #[near_bindgen(rename = "total_supply")]
+    pub fn ft_total_supply(&self) {}
+}
+
+fn main() {}
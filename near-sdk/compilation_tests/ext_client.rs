@@ -0,0 +1,61 @@
+//! Calling a contract through the client generated by `#[ext_contract]`.
+
+use near_sdk::client::{ClientError, Transport};
+use near_sdk::{ext_contract, AccountId, Balance, Gas, Promise, PromiseOrValue};
+use std::cell::RefCell;
+
+#[ext_contract(client)]
+pub trait ExtStatusMessage {
+    #[payable]
+    fn set_status(&mut self, message: String);
+    fn get_status(&self, account_id: String) -> PromiseOrValue<Option<String>>;
+    fn deploy(&mut self, name: String) -> Promise;
+}
+
+/// Transport that records the calls and returns the given result.
+struct MockTransport {
+    calls: RefCell<Vec<(String, Vec<u8>, Balance)>>,
+    result: Vec<u8>,
+}
+
+impl Transport for MockTransport {
+    type Error = String;
+
+    fn view(&self, _contract_id: &AccountId, method_name: &str, args: Vec<u8>) -> Result<Vec<u8>, String> {
+        self.calls.borrow_mut().push((method_name.to_string(), args, 0));
+        Ok(self.result.clone())
+    }
+
+    fn call(
+        &self,
+        _contract_id: &AccountId,
+        method_name: &str,
+        args: Vec<u8>,
+        deposit: Balance,
+        _gas: Gas,
+    ) -> Result<Vec<u8>, String> {
+        self.calls.borrow_mut().push((method_name.to_string(), args, deposit));
+        Ok(vec![])
+    }
+}
+
+fn main() {
+    let transport = MockTransport { calls: RefCell::new(vec![]), result: b"\"hello\"".to_vec() };
+    let client = ext_status_message::Client::connect("status.near".to_string(), transport);
+    client.set_status("hello".to_string(), 10).unwrap();
+    assert_eq!(client.get_status("bob.near".to_string()).unwrap(), Some("hello".to_string()));
+    assert_eq!(
+        client.transport.calls.borrow()[0],
+        ("set_status".to_string(), b"{\"message\":\"hello\"}".to_vec(), 10)
+    );
+    assert_eq!(client.deploy("status".to_string()).unwrap(), Vec::<u8>::new());
+
+    let client = ext_status_message::Client::connect(
+        "status.near".to_string(),
+        MockTransport { calls: RefCell::new(vec![]), result: b"[]".to_vec() },
+    );
+    match client.get_status("bob.near".to_string()) {
+        Err(ClientError::Deserialization(_)) => {}
+        other => panic!("Expected a deserialization error, got {:?}", other),
+    }
+}
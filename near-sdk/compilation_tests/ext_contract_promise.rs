@@ -0,0 +1,23 @@
+//! Traits marked with `#[ext_contract]` can have methods that return `Promise`, since the client
+//! that deserializes the results is only generated with `#[ext_contract(client)]`.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::{ext_contract, near_bindgen, Promise};
+
+#[ext_contract]
+pub trait Factory {
+    fn deploy(&mut self, name: String) -> Promise;
+}
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Deployer {}
+
+#[near_bindgen]
+impl Deployer {
+    pub fn deploy_on(&mut self, factory_id: String, name: String) {
+        factory::deploy(name).on(&factory_id);
+    }
+}
+
+fn main() {}
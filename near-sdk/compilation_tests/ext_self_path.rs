@@ -0,0 +1,45 @@
+//! `ext_self` methods are generated for impls that name the contract through a module path, and a
+//! renamed method of a trait impl does not clash with the method of the same name in another impl.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::near_bindgen;
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+pub struct Counter {
+    value: u64,
+}
+
+pub trait Value {
+    fn value(&self) -> u64;
+}
+
+mod impls {
+    use super::*;
+    use near_sdk::TypedPromise;
+
+    #[near_bindgen]
+    impl Value for super::Counter {
+        #[near_bindgen(rename = "get_value")]
+        fn value(&self) -> u64 {
+            self.value
+        }
+    }
+
+    #[near_bindgen]
+    impl super::Counter {
+        pub fn value(&self) -> u64 {
+            self.value
+        }
+
+        pub fn set(&mut self, value: u64) {
+            self.value = value;
+        }
+
+        pub fn reset_later(&self) -> TypedPromise<u64> {
+            Self::ext_self().set(0).then(Self::ext_self().get_value())
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,39 @@
+//! Generic impl block that is exported for a concrete type.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::near_bindgen;
+
+pub trait Policy: Default + BorshDeserialize + BorshSerialize {
+    fn limit(&self) -> u32;
+}
+
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+pub struct DefaultPolicy {}
+
+impl Policy for DefaultPolicy {
+    fn limit(&self) -> u32 {
+        10
+    }
+}
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+pub struct Incrementer<T> {
+    value: u32,
+    policy: T,
+}
+
+#[near_bindgen(instantiate = "Incrementer<DefaultPolicy>")]
+impl<T: Policy> Incrementer<T> {
+    pub fn inc(&mut self, by: u32) {
+        if by <= self.policy.limit() {
+            self.value += by;
+        }
+    }
+
+    pub fn get(&self) -> u32 {
+        self.value
+    }
+}
+
+fn main() {}
@@ -0,0 +1,36 @@
+//! Smart contract that uses Borsh serialization by default for all methods.
+
+use near_sdk::{ext_contract, near_bindgen, result_serializer, serializer};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Incrementer {
+    value: u32,
+}
+
+#[ext_contract(ext, serializer = borsh)]
+pub trait ExtIncrementer {
+    fn inc(&mut self, by: u32) -> u32;
+    #[result_serializer(json)]
+    fn get(&self) -> u32;
+}
+
+#[near_bindgen(serializer = borsh)]
+impl Incrementer {
+    pub fn inc(&mut self, by: u32) -> u32 {
+        self.value += by;
+        self.value
+    }
+
+    #[result_serializer(json)]
+    pub fn get(&self) -> u32 {
+        self.value
+    }
+
+    pub fn set(&mut self, #[serializer(json)] value: u32) {
+        self.value = value;
+    }
+}
+
+fn main() {}
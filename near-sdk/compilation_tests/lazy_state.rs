@@ -0,0 +1,36 @@
+//! Contract that stores each field under its own key.
+
+use near_sdk::collections::{Lazy, Vector};
+use near_sdk::near_bindgen;
+
+#[near_bindgen(lazy_state)]
+#[derive(Default)]
+struct Incrementer {
+    value: Lazy<u32>,
+    history: Lazy<Vec<u32>>,
+    log: Lazy<Vector<u32>>,
+}
+
+#[near_bindgen]
+impl Incrementer {
+    #[init]
+    pub fn new(value: u32) -> Self {
+        Self { value: value.into(), history: vec![].into(), log: Vector::new(b"l".to_vec()).into() }
+    }
+
+    pub fn inc(&mut self, by: u32) {
+        *self.value += by;
+    }
+
+    pub fn remember(&mut self) {
+        let value = *self.value;
+        self.history.push(value);
+        self.log.push(&value);
+    }
+
+    pub fn get_value(&self) -> u32 {
+        *self.value
+    }
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+//! Fields of the contract with lazy state should be `Lazy`.
+
+use near_sdk::near_bindgen;
+
+#[near_bindgen(lazy_state)]
+#[derive(Default)]
+struct Incrementer {
+    value: near_sdk::collections::Lazy<u32>,
+    total: u64,
+}
+
+fn main() {}
@@ -0,0 +1,36 @@
+//! Contract that migrates its state from the previous version of the layout.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::{near_bindgen, AccountId};
+
+/// The layout of the state before the migration.
+#[derive(BorshDeserialize, BorshSerialize)]
+struct OldIncrementer {
+    value: u32,
+}
+
+#[near_bindgen(state_version = 2)]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Incrementer {
+    value: u64,
+    owner_id: AccountId,
+}
+
+#[near_bindgen]
+impl Incrementer {
+    #[migrate]
+    pub fn migrate(old: OldIncrementer, owner_id: AccountId) -> Self {
+        Self { value: old.value as u64, owner_id }
+    }
+
+    #[init(migrate_from = OldIncrementer)]
+    pub fn migrate_without_owner(old: OldIncrementer) -> Self {
+        Self { value: old.value as u64, owner_id: AccountId::new() }
+    }
+
+    pub fn inc(&mut self, by: u64) {
+        self.value += by;
+    }
+}
+
+fn main() {}
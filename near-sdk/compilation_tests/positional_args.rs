@@ -0,0 +1,70 @@
+//! JSON arguments can be passed as a positional array, in which case trailing `#[default]` and
+//! `Option` arguments can be omitted, unless the impl is marked with `positional_args = false`.
+//! The exported wrappers only exist on wasm, so `main` checks the input parsing they generate for
+//! `transfer` and `transfer_named`, see the `default_args` and `no_positional_args_default` tests
+//! of near-sdk-core.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::near_bindgen;
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Positional {
+    total: u64,
+}
+
+#[near_bindgen]
+impl Positional {
+    pub fn transfer(&mut self, amount: u64, #[default(5)] fee: u64, memo: Option<String>) {
+        let _ = memo;
+        self.total += amount + fee;
+    }
+}
+
+#[near_bindgen(positional_args = false)]
+impl Positional {
+    pub fn transfer_named(&mut self, amount: u64, #[default(5)] fee: u64, memo: Option<String>) {
+        let _ = memo;
+        self.total += amount + fee;
+    }
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+struct Input {
+    amount: u64,
+    #[serde(default = "default_fee")]
+    fee: u64,
+    #[serde(default)]
+    memo: Option<String>,
+}
+
+fn default_fee() -> u64 {
+    5
+}
+
+fn parse(input: &[u8]) -> Result<Input, serde_json::Error> {
+    serde_json::from_slice(input)
+}
+
+fn parse_named(input: &[u8]) -> Option<Input> {
+    if input.iter().find(|b| !b.is_ascii_whitespace()) != Some(&b'{') {
+        return None;
+    }
+    parse(input).ok()
+}
+
+fn main() {
+    let Input { amount, fee, memo } = parse(b"[1]").unwrap();
+    assert_eq!((amount, fee, memo), (1, 5, None));
+    let Input { amount, fee, memo } = parse(b"[1, 2]").unwrap();
+    assert_eq!((amount, fee, memo), (1, 2, None));
+    let Input { amount, fee, memo } = parse(br#"[1, 2, "m"]"#).unwrap();
+    assert_eq!((amount, fee, memo), (1, 2, Some("m".to_string())));
+    assert!(parse(b"[]").is_err());
+    assert!(parse(br#"[1, 2, "m", 3]"#).is_err());
+
+    let Input { amount, fee, memo } = parse_named(br#" {"amount": 1}"#).unwrap();
+    assert_eq!((amount, fee, memo), (1, 5, None));
+    assert!(parse_named(b"[1]").is_none());
+    assert!(parse_named(br#"[1, 2, "m"]"#).is_none());
+}
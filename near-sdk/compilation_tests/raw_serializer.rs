@@ -0,0 +1,31 @@
+//! Smart contract that accepts and returns raw bytes.
+
+use near_sdk::{ext_contract, near_bindgen, result_serializer, serializer};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Storage {
+    data: Vec<u8>,
+}
+
+#[ext_contract]
+pub trait ExtStorage {
+    fn set(&mut self, #[serializer(raw)] data: &[u8]);
+    #[result_serializer(raw)]
+    fn get(&self) -> Vec<u8>;
+}
+
+#[near_bindgen]
+impl Storage {
+    pub fn set(&mut self, #[serializer(raw)] data: &[u8]) {
+        self.data = data.to_vec();
+    }
+
+    #[result_serializer(raw)]
+    pub fn get(&self) -> Vec<u8> {
+        self.data.clone()
+    }
+}
+
+fn main() {}
@@ -0,0 +1,34 @@
+//! Methods exported under a different name and with aliases.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::{ext_contract, near_bindgen};
+
+#[ext_contract]
+pub trait FungibleToken {
+    #[near_bindgen(rename = "ft_total_supply")]
+    fn total_supply(&self) -> U128;
+}
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Token {
+    total_supply: u128,
+}
+
+#[near_bindgen]
+impl Token {
+    #[near_bindgen(rename = "ft_total_supply")]
+    #[alias("total_supply", "get_total_supply")]
+    pub fn total_supply(&self) -> U128 {
+        self.total_supply.into()
+    }
+
+    #[call]
+    pub fn check_supply(&self, token_id: String) {
+        fungible_token::total_supply().on(&token_id);
+        Self::ext_self().ft_total_supply();
+    }
+}
+
+fn main() {}
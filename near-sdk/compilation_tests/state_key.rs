@@ -0,0 +1,29 @@
+//! Contract that stores its state under a custom key and keeps the configuration separately.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::{env, near_bindgen};
+
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Config {
+    max_value: u32,
+}
+
+#[near_bindgen(state_key = "INCREMENTER")]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Incrementer {
+    value: u32,
+}
+
+#[near_bindgen]
+impl Incrementer {
+    pub fn set_max_value(&mut self, max_value: u32) {
+        env::state_write_at(b"CONFIG", &Config { max_value });
+    }
+
+    pub fn inc(&mut self, by: u32) {
+        let config: Config = env::state_read_at(b"CONFIG").unwrap_or_default();
+        self.value = std::cmp::min(self.value + by, config.max_value);
+    }
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+//! Callbacks can not be chained after the promises that produce values of other types.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::{ext_contract, near_bindgen, TypedPromise};
+
+#[ext_contract]
+pub trait ExtStatus {
+    fn get_status(&self) -> String;
+}
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Sum {
+    total: u64,
+}
+
+#[near_bindgen]
+impl Sum {
+    pub fn add(&mut self, status_id: String) -> TypedPromise<u64> {
+        ext_status::get_status().on(&status_id).then(Self::ext_self().on_get())
+    }
+
+    pub fn on_get(&mut self, #[callback] value: u64) -> u64 {
+        self.total += value;
+        self.total
+    }
+}
+
+fn main() {}
@@ -0,0 +1,58 @@
+//! Callbacks chained with `then` read the values produced by the previous promises. The results
+//! of the promises joined with `and` are not checked.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::{ext_contract, near_bindgen, Promise, PromiseOrValue, TypedPromise};
+
+#[ext_contract]
+pub trait ExtCounter {
+    fn get(&self) -> u64;
+    fn get_later(&self) -> PromiseOrValue<u64>;
+}
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Sum {
+    total: u64,
+}
+
+#[near_bindgen]
+impl Sum {
+    pub fn add(&mut self, counter_id: String) -> TypedPromise<u64> {
+        ext_counter::get().on(&counter_id).then(Self::ext_self().on_get())
+    }
+
+    pub fn add_later(&mut self, counter_id: String) -> TypedPromise<()> {
+        ext_counter::get_later().on(&counter_id).then(Self::ext_self().on_get_all())
+    }
+
+    pub fn add_both(&mut self, counter_id: String) -> Promise {
+        ext_counter::get()
+            .on(&counter_id)
+            .and(ext_counter::get().on(&counter_id))
+            .then(Self::ext_self().on_get_both())
+    }
+
+    pub fn log(&mut self, counter_id: String) -> TypedPromise<()> {
+        ext_counter::get().on(&counter_id).then(Self::ext_self().log_total())
+    }
+
+    pub fn on_get(&mut self, #[callback] value: u64) -> u64 {
+        self.total += value;
+        self.total
+    }
+
+    pub fn on_get_all(&mut self, #[callback_vec] values: Vec<u64>) {
+        self.total += values.iter().sum::<u64>();
+    }
+
+    pub fn on_get_both(&mut self, #[callback] a: u64, #[callback] b: u64) {
+        self.total += a + b;
+    }
+
+    pub fn log_total(&self) {
+        near_sdk::env::log(self.total.to_string().as_bytes());
+    }
+}
+
+fn main() {}
@@ -0,0 +1,50 @@
+//! Contract that upgrades its code and migrates its state in the same batch.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::upgrade::Upgrade;
+use near_sdk::{near_bindgen, AccountId, Promise};
+use serde_json::json;
+
+/// The layout of the state before the migration.
+#[derive(BorshDeserialize, BorshSerialize)]
+struct OldCounter {
+    value: u32,
+    owner_id: AccountId,
+}
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Counter {
+    value: u64,
+    owner_id: AccountId,
+    admin_id: AccountId,
+}
+
+#[near_bindgen]
+impl Counter {
+    #[migrate]
+    pub fn migrate(old: OldCounter) -> Self {
+        Self { value: old.value as u64, admin_id: old.owner_id.clone(), owner_id: old.owner_id }
+    }
+
+    #[migrate]
+    pub fn migrate_with_admin(old: OldCounter, admin_id: AccountId) -> Self {
+        Self { value: old.value as u64, owner_id: old.owner_id, admin_id }
+    }
+
+    /// Takes the new code as the raw input.
+    #[call]
+    pub fn upgrade(&self) -> Promise {
+        Upgrade::from_input().deploy(|account_id| account_id == &self.owner_id)
+    }
+
+    #[call]
+    pub fn upgrade_with_admin(&self, admin_id: AccountId) -> Promise {
+        let args = serde_json::to_vec(&json!({ "admin_id": admin_id })).unwrap();
+        Upgrade::from_input()
+            .with_migration("migrate_with_admin", args)
+            .deploy(|account_id| account_id == &self.owner_id)
+    }
+}
+
+fn main() {}
@@ -0,0 +1,37 @@
+//! Smart contract with validated arguments.
+
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{ext_contract, near_bindgen};
+use borsh::{BorshDeserialize, BorshSerialize};
+use std::collections::HashMap;
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Token {
+    balances: HashMap<String, u128>,
+}
+
+#[ext_contract]
+pub trait ExtToken {
+    fn transfer(&mut self, #[validate(account_id)] receiver_id: String, #[validate(range(min = 1))] amount: U128);
+}
+
+#[near_bindgen]
+impl Token {
+    pub fn transfer(
+        &mut self,
+        #[validate(account_id)] receiver_id: String,
+        #[validate(range(min = 1))] amount: U128,
+        #[validate(max_len = 256)] memo: Option<String>,
+        #[validate(range(max = 1000))] fee: Option<U64>,
+    ) {
+        let _ = (memo, fee);
+        let minimum: u128 = 1;
+        if amount == minimum.into() {
+            return;
+        }
+        *self.balances.entry(receiver_id).or_default() += amount.0;
+    }
+}
+
+fn main() {}
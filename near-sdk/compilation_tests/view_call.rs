@@ -0,0 +1,43 @@
+//! Methods explicitly marked as view and call methods.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::collections::Vector;
+use near_sdk::near_bindgen;
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize)]
+struct Incrementer {
+    value: u32,
+    history: Vector<u32>,
+}
+
+impl Default for Incrementer {
+    fn default() -> Self {
+        Self { value: 0, history: Vector::new(b"h".to_vec()) }
+    }
+}
+
+#[near_bindgen]
+impl Incrementer {
+    #[view]
+    pub fn peek(&mut self) -> u32 {
+        self.value += 1;
+        self.value
+    }
+
+    #[call]
+    pub fn record(&self) {
+        let mut history = Vector::new(b"h".to_vec());
+        history.push(&self.value);
+    }
+
+    #[call]
+    #[payable]
+    pub fn deposit(&self) {}
+
+    pub fn get_value(&self) -> u32 {
+        self.value
+    }
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+//! Method cannot be both view and call method.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::near_bindgen;
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Test {}
+
+#[near_bindgen]
+impl Test {
+    #[view]
+    #[call]
+    pub fn get(&self) {}
+}
+
+fn main() {}
@@ -0,0 +1,491 @@
+//! JSON description of the contract interface, the ABI, that can be read by tools that do not
+//! understand Borsh. It is generated together with the metadata by `metadata!{}` macro, and is
+//! returned by `contract_abi` method of the contract or by `contract_abi()` function when the
+//! contract is compiled natively.
+//!
+//! The types of the arguments and the results are described with JSON Schema derived from their
+//! `BorshSchema`, taking into account how `json_types` are serialized with serde.
+use borsh::schema::{BorshSchemaContainer, Declaration, Definition, Fields};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+/// Version of the ABI format.
+const ABI_SEMVER: [u32; 3] = [0, 2, 0];
+
+/// `json_types` that are serialized with serde as strings.
+pub(crate) const STRING_TYPES: &[&str] =
+    &["U128", "U64", "I128", "I64", "Base64VecU8", "Base58PublicKey"];
+
+/// The format in which the arguments or the result of a method are serialized.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+)]
+pub enum SerializerType {
+    #[serde(rename = "json")]
+    JSON,
+    #[serde(rename = "borsh")]
+    Borsh,
+    /// The argument or the result is passed as is, without serialization.
+    #[serde(rename = "raw")]
+    Raw,
+    #[serde(rename = "msgpack")]
+    MessagePack,
+    #[serde(rename = "cbor")]
+    CBOR,
+}
+
+/// The ABI of the contract.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ContractAbi {
+    /// Semver of the ABI format.
+    pub version: [u32; 3],
+    /// ABI of all exported methods.
+    pub methods: Vec<MethodAbi>,
+}
+
+impl ContractAbi {
+    pub fn new(methods: Vec<MethodAbi>) -> Self {
+        Self { version: ABI_SEMVER, methods }
+    }
+
+    /// Serialize the ABI to JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Failed to serialize the ABI using JSON")
+    }
+}
+
+/// ABI of a single method.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct MethodAbi {
+    /// The name under which the method is exported.
+    pub name: String,
+    /// Whether method does not modify the state.
+    pub is_view: bool,
+    /// Whether method can be used to initialize the state.
+    pub is_init: bool,
+    /// Whether method accepts attached deposit.
+    pub is_payable: bool,
+    /// Whether the JSON arguments can also be passed as a positional array.
+    pub positional_args: bool,
+    /// Arguments read from the input.
+    pub args: Vec<ArgAbi>,
+    /// Arguments read from the results of the promises, in the order of the promises.
+    pub callbacks: Vec<ArgAbi>,
+    /// The argument that receives the results of all promises, if any.
+    pub callbacks_vec: Option<ArgAbi>,
+    /// The value returned by the method, if any.
+    pub result: Option<TypeAbi>,
+}
+
+/// ABI of a single argument.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ArgAbi {
+    pub name: String,
+    #[serde(flatten)]
+    pub ty: TypeAbi,
+}
+
+impl ArgAbi {
+    /// ABI of the argument of type `T`.
+    pub fn new<T: BorshSchema>(name: &str, type_name: &str, serializer: SerializerType) -> Self {
+        Self { name: name.to_string(), ty: TypeAbi::new::<T>(type_name, serializer) }
+    }
+}
+
+/// ABI of the type of an argument or a result.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct TypeAbi {
+    /// The Rust type as written in the contract.
+    pub type_name: String,
+    pub serializer: SerializerType,
+    /// JSON Schema of the value. `None` for the raw bytes or if the schema is unknown.
+    pub schema: Option<Value>,
+}
+
+impl TypeAbi {
+    /// ABI of type `T`.
+    pub fn new<T: BorshSchema>(type_name: &str, serializer: SerializerType) -> Self {
+        Self {
+            type_name: type_name.to_string(),
+            serializer,
+            schema: json_schema(&T::schema_container(), serializer),
+        }
+    }
+}
+
+/// JSON Schema of the type described by the Borsh schema, as it is represented in JSON when
+/// serialized with `serializer`. Structs and enums are placed in `definitions` and referenced, so
+/// that recursive types can be described. Returns `None` for the raw bytes.
+pub fn json_schema(container: &BorshSchemaContainer, serializer: SerializerType) -> Option<Value> {
+    if serializer == SerializerType::Raw {
+        return None;
+    }
+    let mut converter = SchemaConverter {
+        definitions: &container.definitions,
+        is_serde: serializer != SerializerType::Borsh,
+        referenced: Map::new(),
+    };
+    let mut schema = converter.inline_schema(&container.declaration);
+    if !converter.referenced.is_empty() {
+        schema["definitions"] = Value::Object(converter.referenced);
+    }
+    Some(schema)
+}
+
+struct SchemaConverter<'a> {
+    definitions: &'a HashMap<Declaration, Definition>,
+    /// Whether `json_types` are represented as strings.
+    is_serde: bool,
+    /// Schemas of the structs and enums that are referenced with `$ref`.
+    referenced: Map<String, Value>,
+}
+
+impl<'a> SchemaConverter<'a> {
+    /// Schema of the type, where structs and enums are referenced.
+    fn schema(&mut self, declaration: &str) -> Value {
+        if self.is_serde && STRING_TYPES.contains(&declaration) {
+            return json!({ "type": "string" });
+        }
+        match self.definitions.get(declaration) {
+            Some(Definition::Struct { .. }) | Some(Definition::Enum { .. })
+                if !is_std_enum(declaration) =>
+            {
+                if !self.referenced.contains_key(declaration) {
+                    // Inserted before the conversion, so that recursive references stop here.
+                    self.referenced.insert(declaration.to_string(), Value::Null);
+                    let schema = self.inline_schema(declaration);
+                    self.referenced.insert(declaration.to_string(), schema);
+                }
+                json!({ "$ref": format!("#/definitions/{}", declaration) })
+            }
+            _ => self.inline_schema(declaration),
+        }
+    }
+
+    /// Schema of the type, where the type itself is not referenced.
+    fn inline_schema(&mut self, declaration: &str) -> Value {
+        if self.is_serde && STRING_TYPES.contains(&declaration) {
+            return json!({ "type": "string" });
+        }
+        let definition = match self.definitions.get(declaration) {
+            Some(definition) => definition,
+            None => return primitive_schema(declaration),
+        };
+        match definition {
+            Definition::Array { length, elements } => json!({
+                "type": "array",
+                "items": self.schema(elements),
+                "minItems": length,
+                "maxItems": length,
+            }),
+            Definition::Sequence { elements } => match self.definitions.get(elements) {
+                // Maps are serialized with serde as objects.
+                Some(Definition::Tuple { elements: key_value })
+                    if self.is_serde
+                        && declaration.starts_with("HashMap<")
+                        && key_value.len() == 2 =>
+                {
+                    json!({ "type": "object", "additionalProperties": self.schema(&key_value[1]) })
+                }
+                _ => json!({ "type": "array", "items": self.schema(elements) }),
+            },
+            Definition::Tuple { elements } => self.tuple_schema(elements),
+            Definition::Enum { variants } if declaration.starts_with("Option<") => {
+                let some = self.schema(&variants[1].1);
+                json!({ "anyOf": [some, { "type": "null" }] })
+            }
+            Definition::Enum { variants } => {
+                let variants: Vec<Value> = variants
+                    .iter()
+                    .map(|(name, variant)| match self.definitions.get(variant) {
+                        Some(Definition::Struct { fields: Fields::Empty }) => {
+                            json!({ "const": name })
+                        }
+                        _ => {
+                            let content = if is_variant_struct(declaration, name, variant) {
+                                self.inline_schema(variant)
+                            } else {
+                                self.schema(variant)
+                            };
+                            let mut properties = Map::new();
+                            properties.insert(name.clone(), content);
+                            json!({
+                                "type": "object",
+                                "properties": properties,
+                                "required": [name],
+                                "additionalProperties": false,
+                            })
+                        }
+                    })
+                    .collect();
+                json!({ "oneOf": variants })
+            }
+            Definition::Struct { fields: Fields::NamedFields(fields) } => {
+                let mut properties = Map::new();
+                let mut required = vec![];
+                for (name, field) in fields {
+                    properties.insert(name.clone(), self.schema(field));
+                    // Serde allows missing `Option` fields.
+                    if !(self.is_serde && field.starts_with("Option<")) {
+                        required.push(name.clone());
+                    }
+                }
+                json!({ "type": "object", "properties": properties, "required": required })
+            }
+            // Newtypes are serialized as the wrapped value.
+            Definition::Struct { fields: Fields::UnnamedFields(fields) } if fields.len() == 1 => {
+                self.schema(&fields[0])
+            }
+            Definition::Struct { fields: Fields::UnnamedFields(fields) } => {
+                self.tuple_schema(fields)
+            }
+            Definition::Struct { fields: Fields::Empty } => json!({ "type": "null" }),
+        }
+    }
+
+    fn tuple_schema(&mut self, elements: &[Declaration]) -> Value {
+        let items: Vec<Value> = elements.iter().map(|element| self.schema(element)).collect();
+        json!({
+            "type": "array",
+            "items": items,
+            "minItems": elements.len(),
+            "maxItems": elements.len(),
+        })
+    }
+}
+
+/// `Option` and `Result` are described inline, since they are generic and cannot be recursive.
+fn is_std_enum(declaration: &str) -> bool {
+    declaration.starts_with("Option<") || declaration.starts_with("Result<")
+}
+
+/// Whether the variant is described by the struct generated for it by `#[derive(BorshSchema)]`,
+/// e.g. `ActionTransfer` for `Action::Transfer`, rather than by another type.
+fn is_variant_struct(enum_declaration: &str, name: &str, variant: &str) -> bool {
+    let enum_name = enum_declaration.split('<').next().unwrap_or(enum_declaration);
+    variant.starts_with(&format!("{}{}", enum_name, name))
+}
+
+/// Schema of the types that do not have Borsh definitions.
+fn primitive_schema(declaration: &str) -> Value {
+    match declaration {
+        "bool" => json!({ "type": "boolean" }),
+        "u8" | "u16" | "u32" | "u64" | "u128" => json!({ "type": "integer", "minimum": 0 }),
+        "i8" | "i16" | "i32" | "i64" | "i128" => json!({ "type": "integer" }),
+        "f32" | "f64" => json!({ "type": "number" }),
+        "string" => json!({ "type": "string" }),
+        "char" => json!({ "type": "string", "minLength": 1, "maxLength": 1 }),
+        "nil" => json!({ "type": "null" }),
+        // Unknown types can have any value.
+        _ => json!({}),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_types::{Base64VecU8, U128};
+
+    #[derive(BorshSchema)]
+    #[allow(dead_code)]
+    pub struct Transfer {
+        receiver_id: String,
+        amount: U128,
+        memo: Option<String>,
+        balances: HashMap<String, u64>,
+    }
+
+    // `BorshSchema` declares a struct with the fields of each variant next to its impl.
+    #[allow(dead_code)]
+    mod action {
+        use super::*;
+
+        #[derive(BorshSchema)]
+        pub enum Action {
+            Stop,
+            Transfer(Transfer),
+            Deploy { code: Base64VecU8, hash: [u8; 2] },
+        }
+    }
+    use action::Action;
+
+    #[test]
+    fn primitives() {
+        assert_eq!(
+            json_schema(&u64::schema_container(), SerializerType::JSON),
+            Some(json!({ "type": "integer", "minimum": 0 }))
+        );
+        assert_eq!(
+            json_schema(&<Vec<(String, bool)>>::schema_container(), SerializerType::JSON),
+            Some(json!({
+                "type": "array",
+                "items": {
+                    "type": "array",
+                    "items": [{ "type": "string" }, { "type": "boolean" }],
+                    "minItems": 2,
+                    "maxItems": 2,
+                }
+            }))
+        );
+        assert_eq!(json_schema(&<Vec<u8>>::schema_container(), SerializerType::Raw), None);
+    }
+
+    #[test]
+    fn json_types() {
+        assert_eq!(
+            json_schema(&U128::schema_container(), SerializerType::JSON),
+            Some(json!({ "type": "string" }))
+        );
+        // With Borsh the value is the wrapped integer.
+        assert_eq!(
+            json_schema(&U128::schema_container(), SerializerType::Borsh),
+            Some(json!({ "type": "integer", "minimum": 0 }))
+        );
+    }
+
+    #[test]
+    fn structs_and_enums() {
+        let transfer = json!({
+            "type": "object",
+            "properties": {
+                "receiver_id": { "type": "string" },
+                "amount": { "type": "string" },
+                "memo": { "anyOf": [{ "type": "string" }, { "type": "null" }] },
+                "balances": {
+                    "type": "object",
+                    "additionalProperties": { "type": "integer", "minimum": 0 },
+                },
+            },
+            "required": ["receiver_id", "amount", "balances"],
+        });
+        assert_eq!(
+            json_schema(&Action::schema_container(), SerializerType::JSON),
+            Some(json!({
+                "oneOf": [
+                    { "const": "Stop" },
+                    {
+                        "type": "object",
+                        "properties": { "Transfer": { "$ref": "#/definitions/Transfer" } },
+                        "required": ["Transfer"],
+                        "additionalProperties": false,
+                    },
+                    {
+                        "type": "object",
+                        "properties": {
+                            "Deploy": {
+                                "type": "object",
+                                "properties": {
+                                    "code": { "type": "string" },
+                                    "hash": {
+                                        "type": "array",
+                                        "items": { "type": "integer", "minimum": 0 },
+                                        "minItems": 2,
+                                        "maxItems": 2,
+                                    },
+                                },
+                                "required": ["code", "hash"],
+                            }
+                        },
+                        "required": ["Deploy"],
+                        "additionalProperties": false,
+                    },
+                ],
+                "definitions": { "Transfer": transfer },
+            }))
+        );
+    }
+
+    #[test]
+    fn recursive_types() {
+        // `#[derive(BorshSchema)]` does not support recursive types, so the schema is written
+        // explicitly.
+        let mut definitions = HashMap::new();
+        definitions.insert(
+            "Node".to_string(),
+            Definition::Struct {
+                fields: Fields::NamedFields(vec![
+                    ("value".to_string(), "u8".to_string()),
+                    ("children".to_string(), "Vec<Node>".to_string()),
+                ]),
+            },
+        );
+        definitions
+            .insert("Vec<Node>".to_string(), Definition::Sequence { elements: "Node".to_string() });
+        let container = BorshSchemaContainer { declaration: "Node".to_string(), definitions };
+        assert_eq!(
+            json_schema(&container, SerializerType::JSON),
+            Some(json!({
+                "type": "object",
+                "properties": {
+                    "value": { "type": "integer", "minimum": 0 },
+                    "children": { "type": "array", "items": { "$ref": "#/definitions/Node" } },
+                },
+                "required": ["value", "children"],
+                "definitions": {
+                    "Node": {
+                        "type": "object",
+                        "properties": {
+                            "value": { "type": "integer", "minimum": 0 },
+                            "children": {
+                                "type": "array",
+                                "items": { "$ref": "#/definitions/Node" },
+                            },
+                        },
+                        "required": ["value", "children"],
+                    }
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn method_abi_json() {
+        let abi = ContractAbi::new(vec![MethodAbi {
+            name: "transfer".to_string(),
+            is_view: false,
+            is_init: false,
+            is_payable: true,
+            positional_args: true,
+            args: vec![ArgAbi::new::<U128>("amount", "U128", SerializerType::JSON)],
+            callbacks: vec![],
+            callbacks_vec: None,
+            result: Some(TypeAbi::new::<Vec<u8>>("Vec<u8>", SerializerType::Raw)),
+        }]);
+        let json: Value = serde_json::from_str(&abi.to_json()).unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "version": [0, 2, 0],
+                "methods": [{
+                    "name": "transfer",
+                    "is_view": false,
+                    "is_init": false,
+                    "is_payable": true,
+                    "positional_args": true,
+                    "args": [{
+                        "name": "amount",
+                        "type_name": "U128",
+                        "serializer": "json",
+                        "schema": { "type": "string" },
+                    }],
+                    "callbacks": [],
+                    "callbacks_vec": null,
+                    "result": { "type_name": "Vec<u8>", "serializer": "raw", "schema": null },
+                }],
+            })
+        );
+        let parsed: ContractAbi = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, abi);
+    }
+}
@@ -0,0 +1,708 @@
+//! Conversion of Borsh-serialized values to JSON and back, driven by the `BorshSchema` of their
+//! type, e.g. the schemas of the arguments and the results in the metadata of the contract. It lets
+//! explorers, test tools and state inspectors display and construct any value without compiling
+//! the types of the contract:
+//! ```ignore
+//! let args = borsh_json::decode(method.args.as_ref().unwrap(), &input)?;
+//! let input = borsh_json::encode(method.args.as_ref().unwrap(), &args)?;
+//! ```
+//! Values are represented the way serde represents them in JSON: `json_types` are strings, e.g.
+//! `U64` is a decimal and `Base64VecU8` is base64, maps are objects, `None` is `null`, newtypes are
+//! unwrapped and enums are either the name of the unit variant or an object with the variant name as
+//! the only key. The exception are the 128-bit integers that do not fit into `serde_json::Value`
+//! and are represented as strings too.
+use crate::abi::STRING_TYPES;
+use crate::json_types::{Base58PublicKey, Base64VecU8, I128, I64, U128, U64};
+use borsh::schema::{BorshSchemaContainer, Declaration, Definition, Fields};
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+
+/// An error returned when the value cannot be converted.
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// The data ended before the whole value was decoded.
+    UnexpectedEnd,
+    /// The data has bytes left after the value.
+    TrailingBytes(usize),
+    /// The type neither has a definition in the schema nor is a supported primitive.
+    UnknownType(Declaration),
+    /// The bytes or the JSON value do not represent a value of the type.
+    InvalidValue { declaration: Declaration, message: String },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::UnexpectedEnd => write!(f, "Unexpected end of the data"),
+            Error::TrailingBytes(len) => write!(f, "{} bytes left after the value", len),
+            Error::UnknownType(declaration) => write!(f, "Unknown type `{}`", declaration),
+            Error::InvalidValue { declaration, message } => {
+                write!(f, "Invalid value of `{}`: {}", declaration, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+fn invalid(declaration: &str, message: impl ToString) -> Error {
+    Error::InvalidValue { declaration: declaration.to_string(), message: message.to_string() }
+}
+
+/// Decodes the Borsh-serialized value of the type described by the schema into JSON.
+pub fn decode(container: &BorshSchemaContainer, data: &[u8]) -> Result<Value, Error> {
+    let mut decoder = Decoder { definitions: &container.definitions, data };
+    let value = decoder.decode(&container.declaration)?;
+    if !decoder.data.is_empty() {
+        return Err(Error::TrailingBytes(decoder.data.len()));
+    }
+    Ok(value)
+}
+
+/// Serializes the JSON value of the type described by the schema with Borsh.
+pub fn encode(container: &BorshSchemaContainer, value: &Value) -> Result<Vec<u8>, Error> {
+    let mut encoder = Encoder { definitions: &container.definitions, data: vec![] };
+    encoder.encode(&container.declaration, value)?;
+    Ok(encoder.data)
+}
+
+/// The types of the key and the value, if the sequence is a `HashMap`.
+fn map_entry<'a>(
+    definitions: &'a HashMap<Declaration, Definition>,
+    declaration: &str,
+    elements: &str,
+) -> Option<(&'a Declaration, &'a Declaration)> {
+    match definitions.get(elements) {
+        Some(Definition::Tuple { elements }) if declaration.starts_with("HashMap<") => {
+            match elements.as_slice() {
+                [key, value] => Some((key, value)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Converts between the index of the variant in the schema and the byte that is serialized.
+/// `Result` is serialized with `Err` as the first variant, while its schema lists `Ok` first.
+fn variant_index(declaration: &str, index: u8) -> u8 {
+    if declaration.starts_with("Result<") && index < 2 {
+        1 - index
+    } else {
+        index
+    }
+}
+
+/// Reads the little-endian integer of the given type.
+macro_rules! read_le {
+    ($decoder:expr, $ty:ty) => {{
+        let mut bytes = [0u8; std::mem::size_of::<$ty>()];
+        bytes.copy_from_slice($decoder.take(std::mem::size_of::<$ty>())?);
+        <$ty>::from_le_bytes(bytes)
+    }};
+}
+
+struct Decoder<'a> {
+    definitions: &'a HashMap<Declaration, Definition>,
+    /// The bytes that are not decoded yet.
+    data: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.data.len() < len {
+            return Err(Error::UnexpectedEnd);
+        }
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+        Ok(head)
+    }
+
+    /// Reads the length of a sequence. It is bounded by the number of remaining bytes, so that a
+    /// corrupted length does not make it allocate a huge vector, e.g. of zero-sized `()`.
+    fn length(&mut self) -> Result<usize, Error> {
+        let len = read_le!(self, u32) as usize;
+        if len > self.data.len() {
+            return Err(Error::UnexpectedEnd);
+        }
+        Ok(len)
+    }
+
+    fn decode_all(&mut self, declarations: &[Declaration]) -> Result<Vec<Value>, Error> {
+        declarations.iter().map(|declaration| self.decode(declaration)).collect()
+    }
+
+    /// Decodes the value with its Borsh implementation and converts it with serde.
+    fn decode_serde<T: BorshDeserialize + Serialize>(
+        &mut self,
+        declaration: &str,
+    ) -> Result<Value, Error> {
+        let value = T::deserialize(&mut self.data).map_err(|err| match err.kind() {
+            std::io::ErrorKind::UnexpectedEof => Error::UnexpectedEnd,
+            _ => invalid(declaration, err),
+        })?;
+        serde_json::to_value(value).map_err(|err| invalid(declaration, err))
+    }
+
+    fn decode(&mut self, declaration: &str) -> Result<Value, Error> {
+        if STRING_TYPES.contains(&declaration) {
+            return match declaration {
+                "U128" => self.decode_serde::<U128>(declaration),
+                "U64" => self.decode_serde::<U64>(declaration),
+                "I128" => self.decode_serde::<I128>(declaration),
+                "I64" => self.decode_serde::<I64>(declaration),
+                "Base64VecU8" => self.decode_serde::<Base64VecU8>(declaration),
+                _ => self.decode_serde::<Base58PublicKey>(declaration),
+            };
+        }
+        let definition = match self.definitions.get(declaration) {
+            Some(definition) => definition,
+            None => return self.decode_primitive(declaration),
+        };
+        match definition {
+            Definition::Array { length, elements } => (0..*length)
+                .map(|_| self.decode(elements))
+                .collect::<Result<_, _>>()
+                .map(Value::Array),
+            Definition::Sequence { elements } => {
+                let len = self.length()?;
+                match map_entry(self.definitions, declaration, elements) {
+                    Some((key, value)) => {
+                        let mut map = Map::new();
+                        for _ in 0..len {
+                            let key = match self.decode(key)? {
+                                Value::String(key) => key,
+                                key => key.to_string(),
+                            };
+                            map.insert(key, self.decode(value)?);
+                        }
+                        Ok(Value::Object(map))
+                    }
+                    None => (0..len)
+                        .map(|_| self.decode(elements))
+                        .collect::<Result<_, _>>()
+                        .map(Value::Array),
+                }
+            }
+            Definition::Tuple { elements } => self.decode_all(elements).map(Value::Array),
+            Definition::Enum { variants } => {
+                let index = variant_index(declaration, self.take(1)?[0]);
+                let (name, variant) = variants.get(index as usize).ok_or_else(|| {
+                    invalid(declaration, format!("unknown variant index {}", index))
+                })?;
+                if declaration.starts_with("Option<") {
+                    return if index == 0 { Ok(Value::Null) } else { self.decode(variant) };
+                }
+                match self.definitions.get(variant) {
+                    Some(Definition::Struct { fields: Fields::Empty }) => {
+                        Ok(Value::String(name.clone()))
+                    }
+                    _ => {
+                        let mut map = Map::new();
+                        map.insert(name.clone(), self.decode(variant)?);
+                        Ok(Value::Object(map))
+                    }
+                }
+            }
+            Definition::Struct { fields: Fields::NamedFields(fields) } => {
+                let mut map = Map::new();
+                for (name, field) in fields {
+                    map.insert(name.clone(), self.decode(field)?);
+                }
+                Ok(Value::Object(map))
+            }
+            Definition::Struct { fields: Fields::UnnamedFields(fields) } if fields.len() == 1 => {
+                self.decode(&fields[0])
+            }
+            Definition::Struct { fields: Fields::UnnamedFields(fields) } => {
+                self.decode_all(fields).map(Value::Array)
+            }
+            Definition::Struct { fields: Fields::Empty } => Ok(Value::Null),
+        }
+    }
+
+    fn decode_primitive(&mut self, declaration: &str) -> Result<Value, Error> {
+        Ok(match declaration {
+            "bool" => match self.take(1)?[0] {
+                0 => Value::Bool(false),
+                1 => Value::Bool(true),
+                byte => return Err(invalid(declaration, format!("unexpected byte {}", byte))),
+            },
+            "u8" => Value::from(read_le!(self, u8)),
+            "u16" => Value::from(read_le!(self, u16)),
+            "u32" => Value::from(read_le!(self, u32)),
+            "u64" => Value::from(read_le!(self, u64)),
+            "u128" => Value::String(read_le!(self, u128).to_string()),
+            "i8" => Value::from(read_le!(self, i8)),
+            "i16" => Value::from(read_le!(self, i16)),
+            "i32" => Value::from(read_le!(self, i32)),
+            "i64" => Value::from(read_le!(self, i64)),
+            "i128" => Value::String(read_le!(self, i128).to_string()),
+            "f32" => float(declaration, read_le!(self, f32) as f64)?,
+            "f64" => float(declaration, read_le!(self, f64))?,
+            "char" => {
+                let code = read_le!(self, u32);
+                let c = std::char::from_u32(code)
+                    .ok_or_else(|| invalid(declaration, format!("invalid code point {}", code)))?;
+                Value::String(c.to_string())
+            }
+            "string" => {
+                let len = self.length()?;
+                let bytes = self.take(len)?.to_vec();
+                Value::String(String::from_utf8(bytes).map_err(|err| invalid(declaration, err))?)
+            }
+            "nil" => Value::Null,
+            _ => return Err(Error::UnknownType(declaration.to_string())),
+        })
+    }
+}
+
+fn float(declaration: &str, value: f64) -> Result<Value, Error> {
+    Number::from_f64(value).map(Value::Number).ok_or_else(|| invalid(declaration, "not a number"))
+}
+
+struct Encoder<'a> {
+    definitions: &'a HashMap<Declaration, Definition>,
+    data: Vec<u8>,
+}
+
+impl<'a> Encoder<'a> {
+    fn length(&mut self, len: usize) {
+        self.data.extend_from_slice(&(len as u32).to_le_bytes());
+    }
+
+    /// Encodes the value separately from the data, e.g. to sort the entries of a map.
+    fn encode_separately(&self, declaration: &str, value: &Value) -> Result<Vec<u8>, Error> {
+        let mut encoder = Encoder { definitions: self.definitions, data: vec![] };
+        encoder.encode(declaration, value)?;
+        Ok(encoder.data)
+    }
+
+    fn encode_all(&mut self, declarations: &[Declaration], value: &Value) -> Result<(), Error> {
+        match value {
+            Value::Array(items) if items.len() == declarations.len() => {
+                for (declaration, item) in declarations.iter().zip(items) {
+                    self.encode(declaration, item)?;
+                }
+                Ok(())
+            }
+            _ => Err(invalid(
+                &format!("Tuple<{}>", declarations.join(", ")),
+                format!("expected an array of {} items, found {}", declarations.len(), value),
+            )),
+        }
+    }
+
+    /// Converts the value with serde and encodes it with its Borsh implementation.
+    fn encode_serde<T: DeserializeOwned + BorshSerialize>(
+        &mut self,
+        declaration: &str,
+        value: &Value,
+    ) -> Result<(), Error> {
+        let value: T =
+            serde_json::from_value(value.clone()).map_err(|err| invalid(declaration, err))?;
+        value.serialize(&mut self.data).map_err(|err| invalid(declaration, err))
+    }
+
+    fn encode(&mut self, declaration: &str, value: &Value) -> Result<(), Error> {
+        if STRING_TYPES.contains(&declaration) {
+            return match declaration {
+                "U128" => self.encode_serde::<U128>(declaration, value),
+                "U64" => self.encode_serde::<U64>(declaration, value),
+                "I128" => self.encode_serde::<I128>(declaration, value),
+                "I64" => self.encode_serde::<I64>(declaration, value),
+                "Base64VecU8" => self.encode_serde::<Base64VecU8>(declaration, value),
+                _ => self.encode_serde::<Base58PublicKey>(declaration, value),
+            };
+        }
+        let definition = match self.definitions.get(declaration) {
+            Some(definition) => definition,
+            None => return self.encode_primitive(declaration, value),
+        };
+        match definition {
+            Definition::Array { length, elements } => match value {
+                Value::Array(items) if items.len() == *length as usize => {
+                    items.iter().try_for_each(|item| self.encode(elements, item))
+                }
+                _ => Err(invalid(
+                    declaration,
+                    format!("expected an array of {} items, found {}", length, value),
+                )),
+            },
+            Definition::Sequence { elements } => {
+                match (map_entry(self.definitions, declaration, elements), value) {
+                    (Some((key, value)), Value::Object(map)) => {
+                        let mut entries = vec![];
+                        for (key_string, item) in map {
+                            let encoded_key = self.encode_key(key, key_string)?;
+                            let encoded_value = self.encode_separately(value, item)?;
+                            entries.push((
+                                order_key(key, &encoded_key),
+                                encoded_key,
+                                encoded_value,
+                            ));
+                        }
+                        // Borsh serializes the entries sorted by their keys.
+                        entries.sort_by(|a, b| a.0.cmp(&b.0));
+                        self.length(entries.len());
+                        for (_, encoded_key, encoded_value) in entries {
+                            self.data.extend(encoded_key);
+                            self.data.extend(encoded_value);
+                        }
+                        Ok(())
+                    }
+                    (None, Value::Array(items)) => {
+                        self.length(items.len());
+                        items.iter().try_for_each(|item| self.encode(elements, item))
+                    }
+                    (Some(_), _) => {
+                        Err(invalid(declaration, format!("expected an object, found {}", value)))
+                    }
+                    (None, _) => {
+                        Err(invalid(declaration, format!("expected an array, found {}", value)))
+                    }
+                }
+            }
+            Definition::Tuple { elements } => self.encode_all(elements, value),
+            Definition::Enum { variants } if declaration.starts_with("Option<") => match value {
+                Value::Null => {
+                    self.data.push(0);
+                    Ok(())
+                }
+                _ => {
+                    self.data.push(1);
+                    self.encode(&variants[1].1, value)
+                }
+            },
+            Definition::Enum { variants } => {
+                let (name, content) = match value {
+                    Value::String(name) => (name, None),
+                    Value::Object(map) if map.len() == 1 => {
+                        let (name, content) = map.iter().next().unwrap();
+                        (name, Some(content))
+                    }
+                    _ => {
+                        return Err(invalid(
+                            declaration,
+                            format!("expected a variant name or an object, found {}", value),
+                        ))
+                    }
+                };
+                let index = variants
+                    .iter()
+                    .position(|(variant_name, _)| variant_name == name)
+                    .ok_or_else(|| invalid(declaration, format!("unknown variant `{}`", name)))?;
+                self.data.push(variant_index(declaration, index as u8));
+                let variant = &variants[index].1;
+                match (self.definitions.get(variant), content) {
+                    (Some(Definition::Struct { fields: Fields::Empty }), None) => Ok(()),
+                    (_, Some(content)) => self.encode(variant, content),
+                    (_, None) => Err(invalid(
+                        declaration,
+                        format!("variant `{}` is expected to have a value", name),
+                    )),
+                }
+            }
+            Definition::Struct { fields: Fields::NamedFields(fields) } => {
+                let map = match value {
+                    Value::Object(map) => map,
+                    _ => {
+                        return Err(invalid(
+                            declaration,
+                            format!("expected an object, found {}", value),
+                        ))
+                    }
+                };
+                if let Some(name) =
+                    map.keys().find(|name| fields.iter().all(|(field_name, _)| field_name != *name))
+                {
+                    return Err(invalid(declaration, format!("unknown field `{}`", name)));
+                }
+                for (name, field) in fields {
+                    match map.get(name) {
+                        Some(item) => self.encode(field, item)?,
+                        // Serde allows missing `Option` fields.
+                        None if field.starts_with("Option<") => self.data.push(0),
+                        None => {
+                            return Err(invalid(declaration, format!("missing field `{}`", name)))
+                        }
+                    }
+                }
+                Ok(())
+            }
+            Definition::Struct { fields: Fields::UnnamedFields(fields) } if fields.len() == 1 => {
+                self.encode(&fields[0], value)
+            }
+            Definition::Struct { fields: Fields::UnnamedFields(fields) } => {
+                self.encode_all(fields, value)
+            }
+            Definition::Struct { fields: Fields::Empty } => match value {
+                Value::Null => Ok(()),
+                _ => Err(invalid(declaration, format!("expected null, found {}", value))),
+            },
+        }
+    }
+
+    /// Encodes the key of a map, which is either the string value of the key or its JSON.
+    fn encode_key(&self, declaration: &str, key: &str) -> Result<Vec<u8>, Error> {
+        self.encode_separately(declaration, &Value::String(key.to_string())).or_else(|err| {
+            match serde_json::from_str::<Value>(key) {
+                Ok(value) => self.encode_separately(declaration, &value),
+                Err(_) => Err(err),
+            }
+        })
+    }
+
+    fn encode_primitive(&mut self, declaration: &str, value: &Value) -> Result<(), Error> {
+        let expected =
+            |kind: &str| invalid(declaration, format!("expected {}, found {}", kind, value));
+        match declaration {
+            "bool" => {
+                let value = value.as_bool().ok_or_else(|| expected("a boolean"))?;
+                self.data.push(value as u8);
+            }
+            "u8" => self.data.extend(&unsigned::<u8>(declaration, value)?.to_le_bytes()),
+            "u16" => self.data.extend(&unsigned::<u16>(declaration, value)?.to_le_bytes()),
+            "u32" => self.data.extend(&unsigned::<u32>(declaration, value)?.to_le_bytes()),
+            "u64" => self.data.extend(&unsigned::<u64>(declaration, value)?.to_le_bytes()),
+            "u128" => self.data.extend(&unsigned::<u128>(declaration, value)?.to_le_bytes()),
+            "i8" => self.data.extend(&signed::<i8>(declaration, value)?.to_le_bytes()),
+            "i16" => self.data.extend(&signed::<i16>(declaration, value)?.to_le_bytes()),
+            "i32" => self.data.extend(&signed::<i32>(declaration, value)?.to_le_bytes()),
+            "i64" => self.data.extend(&signed::<i64>(declaration, value)?.to_le_bytes()),
+            "i128" => self.data.extend(&signed::<i128>(declaration, value)?.to_le_bytes()),
+            "f32" => {
+                let value = value.as_f64().ok_or_else(|| expected("a number"))?;
+                self.data.extend(&(value as f32).to_le_bytes());
+            }
+            "f64" => {
+                let value = value.as_f64().ok_or_else(|| expected("a number"))?;
+                self.data.extend(&value.to_le_bytes());
+            }
+            "char" => {
+                let mut chars = value.as_str().ok_or_else(|| expected("a character"))?.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => self.data.extend(&(c as u32).to_le_bytes()),
+                    _ => return Err(expected("a character")),
+                }
+            }
+            "string" => {
+                let value = value.as_str().ok_or_else(|| expected("a string"))?;
+                self.length(value.len());
+                self.data.extend(value.as_bytes());
+            }
+            "nil" => {
+                if !value.is_null() {
+                    return Err(expected("null"));
+                }
+            }
+            _ => return Err(Error::UnknownType(declaration.to_string())),
+        }
+        Ok(())
+    }
+}
+
+/// Integers are accepted both as numbers and as strings.
+fn unsigned<T: TryFrom<u128>>(declaration: &str, value: &Value) -> Result<T, Error> {
+    let parsed = match value {
+        Value::Number(number) => number.as_u64().map(u128::from),
+        Value::String(string) => string.parse().ok(),
+        _ => None,
+    };
+    parsed.and_then(|parsed| T::try_from(parsed).ok()).ok_or_else(|| {
+        invalid(declaration, format!("expected an unsigned integer, found {}", value))
+    })
+}
+
+fn signed<T: TryFrom<i128>>(declaration: &str, value: &Value) -> Result<T, Error> {
+    let parsed = match value {
+        Value::Number(number) => number.as_i64().map(i128::from),
+        Value::String(string) => string.parse().ok(),
+        _ => None,
+    };
+    parsed
+        .and_then(|parsed| T::try_from(parsed).ok())
+        .ok_or_else(|| invalid(declaration, format!("expected an integer, found {}", value)))
+}
+
+/// Bytes that are ordered the same way as the encoded keys of the given type: big-endian integers
+/// with the sign bit flipped, and strings without their length.
+fn order_key(declaration: &str, encoded: &[u8]) -> Vec<u8> {
+    let mut key = encoded.to_vec();
+    match declaration {
+        "u8" | "u16" | "u32" | "u64" | "u128" => key.reverse(),
+        "i8" | "i16" | "i32" | "i64" | "i128" => {
+            key.reverse();
+            key[0] ^= 0x80;
+        }
+        "string" => {
+            key.drain(..4);
+        }
+        _ => {}
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_types::{Base58PublicKey, Base64VecU8, I64, U128, U64};
+    use borsh::{BorshSchema, BorshSerialize};
+    use serde_json::json;
+    use std::convert::TryFrom;
+
+    #[derive(BorshSerialize, BorshSchema)]
+    pub struct Transfer {
+        receiver_id: String,
+        amount: U128,
+        memo: Option<String>,
+        balances: HashMap<u64, i32>,
+    }
+
+    // `BorshSchema` declares a struct with the fields of each variant next to its impl.
+    #[allow(dead_code)]
+    mod action {
+        use super::*;
+
+        #[derive(BorshSerialize, BorshSchema)]
+        pub enum Action {
+            Stop,
+            Transfer(Transfer),
+            Deploy { code: Base64VecU8, hash: [u8; 2] },
+            Swap(u128, (bool, i8)),
+        }
+    }
+    use action::Action;
+
+    fn assert_converts<T: BorshSerialize + BorshSchema>(value: T, expected: Value) {
+        let container = T::schema_container();
+        let data = value.try_to_vec().unwrap();
+        assert_eq!(decode(&container, &data).unwrap(), expected);
+        assert_eq!(encode(&container, &expected).unwrap(), data);
+    }
+
+    fn transfer() -> Transfer {
+        let mut balances = HashMap::new();
+        balances.insert(2, -1);
+        balances.insert(10, 1);
+        Transfer {
+            receiver_id: "bob.near".to_string(),
+            amount: U128(u128::MAX),
+            memo: None,
+            balances,
+        }
+    }
+
+    #[test]
+    fn primitives() {
+        assert_converts(true, json!(true));
+        assert_converts(-5i8, json!(-5));
+        assert_converts(u64::MAX, json!(u64::MAX));
+        assert_converts(u128::MAX, json!(u128::MAX.to_string()));
+        assert_converts(-1.5f64, json!(-1.5));
+        assert_converts("hello".to_string(), json!("hello"));
+        // Borsh has a schema for `char`, but does not serialize it.
+        let container = char::schema_container();
+        assert_eq!(decode(&container, &('ы' as u32).to_le_bytes()).unwrap(), json!("ы"));
+        assert_eq!(encode(&container, &json!("ы")).unwrap(), ('ы' as u32).to_le_bytes());
+        assert_eq!(decode(&<()>::schema_container(), &[]).unwrap(), json!(null));
+    }
+
+    #[test]
+    fn collections() {
+        assert_converts(vec![1u8, 2], json!([1, 2]));
+        assert_converts([3u16, 4], json!([3, 4]));
+        assert_converts(Some(1u32), json!(1));
+        assert_converts(None::<u32>, json!(null));
+        assert_converts(("a".to_string(), 1i64), json!(["a", 1]));
+        assert_converts(Ok::<u8, String>(1), json!({ "Ok": 1 }));
+        assert_converts(Err::<u8, String>("e".to_string()), json!({ "Err": "e" }));
+    }
+
+    #[test]
+    fn json_types() {
+        assert_converts(U64(u64::MAX), json!(u64::MAX.to_string()));
+        assert_converts(I64(-5), json!("-5"));
+        assert_converts(Base64VecU8(vec![1, 2, 3]), json!("AQID"));
+        let key = "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp";
+        assert_converts(Base58PublicKey::try_from(key).unwrap(), json!(key));
+    }
+
+    #[test]
+    fn structs_and_enums() {
+        let expected = json!({
+            "receiver_id": "bob.near",
+            "amount": u128::MAX.to_string(),
+            "memo": null,
+            "balances": { "2": -1, "10": 1 },
+        });
+        assert_converts(transfer(), expected.clone());
+        assert_converts(Action::Stop, json!("Stop"));
+        assert_converts(Action::Transfer(transfer()), json!({ "Transfer": expected }));
+        assert_converts(
+            Action::Deploy { code: Base64VecU8(vec![1]), hash: [2, 3] },
+            json!({ "Deploy": { "code": "AQ==", "hash": [2, 3] } }),
+        );
+        assert_converts(Action::Swap(7, (true, -1)), json!({ "Swap": ["7", [true, -1]] }));
+    }
+
+    #[test]
+    fn encode_accepts_missing_options_and_integers() {
+        let container = Transfer::schema_container();
+        let value = json!({
+            "receiver_id": "bob.near",
+            "amount": "5",
+            "balances": { "10": "1", "2": -1 },
+        });
+        let mut expected = transfer();
+        expected.amount = U128(5);
+        assert_eq!(encode(&container, &value).unwrap(), expected.try_to_vec().unwrap());
+    }
+
+    #[test]
+    fn errors() {
+        let container = Action::schema_container();
+        assert_eq!(decode(&container, &[]), Err(Error::UnexpectedEnd));
+        assert_eq!(decode(&container, &[0, 0]), Err(Error::TrailingBytes(1)));
+        assert_eq!(
+            decode(&container, &[9]).unwrap_err().to_string(),
+            "Invalid value of `Action`: unknown variant index 9"
+        );
+        assert_eq!(
+            encode(&container, &json!("Pause")).unwrap_err().to_string(),
+            "Invalid value of `Action`: unknown variant `Pause`"
+        );
+        assert_eq!(
+            encode(&Transfer::schema_container(), &json!({ "amount": "1" }))
+                .unwrap_err()
+                .to_string(),
+            "Invalid value of `Transfer`: missing field `receiver_id`"
+        );
+        assert_eq!(
+            encode(&u8::schema_container(), &json!(256)).unwrap_err().to_string(),
+            "Invalid value of `u8`: expected an unsigned integer, found 256"
+        );
+        assert_eq!(
+            decode(&char::schema_container(), &[0, 0xd8, 0, 0]).unwrap_err().to_string(),
+            "Invalid value of `char`: invalid code point 55296"
+        );
+        assert_eq!(
+            encode(&char::schema_container(), &json!("ab")).unwrap_err().to_string(),
+            "Invalid value of `char`: expected a character, found \"ab\""
+        );
+        assert_eq!(
+            encode(&U64::schema_container(), &json!(5)).unwrap_err().to_string(),
+            "Invalid value of `U64`: invalid type: integer `5`, expected a string"
+        );
+        // The length of a sequence of zero-sized elements is bounded by the remaining data.
+        assert_eq!(
+            decode(&Vec::<()>::schema_container(), &[0xff, 0xff, 0xff, 0xff]),
+            Err(Error::UnexpectedEnd)
+        );
+    }
+}
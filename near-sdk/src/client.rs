@@ -0,0 +1,66 @@
+//! Calling contracts from outside of the blockchain, e.g. from a service or a test, with typed
+//! clients. Every `#[ext_contract]` module contains `Client` that has a method for each method of
+//! the trait. These methods serialize the arguments, pass them to a `Transport` that executes the
+//! call, and deserialize the result:
+//! ```ignore
+//! #[ext_contract]
+//! pub trait ExtStatusMessage {
+//!     #[payable]
+//!     fn set_status(&mut self, message: String);
+//!     fn get_status(&self, account_id: String) -> Option<String>;
+//! }
+//!
+//! let client = ext_status_message::Client::connect("status.near".to_string(), transport);
+//! client.set_status("hello".to_string(), 1_000)?;
+//! assert_eq!(client.get_status("bob.near".to_string())?, Some("hello".to_string()));
+//! ```
+//! Methods with `&self` receiver are called as view methods, the other ones in transactions with
+//! `Client::gas` gas attached. Payable methods take the attached deposit as the last argument.
+use crate::{AccountId, Balance, Gas};
+use std::fmt;
+
+/// Executes the calls of the contract methods, e.g. by sending JSON RPC requests to a node.
+pub trait Transport {
+    type Error;
+
+    /// Calls the view method with serialized arguments and returns its serialized result.
+    fn view(
+        &self,
+        contract_id: &AccountId,
+        method_name: &str,
+        args: Vec<u8>,
+    ) -> Result<Vec<u8>, Self::Error>;
+
+    /// Calls the method in a transaction and returns its serialized result once the transaction is
+    /// executed.
+    fn call(
+        &self,
+        contract_id: &AccountId,
+        method_name: &str,
+        args: Vec<u8>,
+        deposit: Balance,
+        gas: Gas,
+    ) -> Result<Vec<u8>, Self::Error>;
+}
+
+/// An error returned by the methods of the clients.
+#[derive(Debug, PartialEq)]
+pub enum ClientError<E> {
+    /// The transport failed to execute the call.
+    Transport(E),
+    /// The result of the call cannot be deserialized.
+    Deserialization(String),
+}
+
+impl<E: fmt::Display> fmt::Display for ClientError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClientError::Transport(err) => write!(f, "Failed to execute the call: {}", err),
+            ClientError::Deserialization(err) => {
+                write!(f, "Failed to deserialize the result of the call: {}", err)
+            }
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for ClientError<E> {}
@@ -0,0 +1,272 @@
+//! Staging of a blob, e.g. contract code, that is too large to be passed in a single call. The blob
+//! is uploaded in chunks across several calls and is taken as a whole once its hash is verified.
+use crate::collections::Vector;
+use crate::{env, Promise};
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_vm_logic::types::{AccountId, Balance};
+
+const ERR_HASH_MISMATCH: &[u8] = b"The hash of the staged blob does not match the expected hash.";
+const ERR_EMPTY_BLOB: &[u8] = b"No chunks of the blob are staged.";
+
+/// A blob uploaded in numbered chunks, that are stored on the trie under `id` prefix. Only the
+/// uploader, e.g. the owner of the contract, can add chunks, so that other accounts can not occupy
+/// them. It pays for the storage of the chunks with the attached deposit and gets the deposit back
+/// when the blob is taken or cleared.
+/// ```ignore
+/// #[init]
+/// pub fn new(owner_id: AccountId) -> Self {
+///     Self { code: BlobStaging::new(b"c".to_vec(), 10u128.pow(19), owner_id.clone()), owner_id }
+/// }
+///
+/// #[payable]
+/// pub fn stage_code(&mut self, index: u64, chunk: Base64VecU8) {
+///     self.code.add_chunk(index, &chunk.0);
+/// }
+///
+/// pub fn upgrade(&mut self, sha256: Base64VecU8) -> Promise {
+///     let code = self.code.take(&sha256.0);
+///     Upgrade::new(code).deploy(|account_id| account_id == &self.owner_id)
+/// }
+/// ```
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct BlobStaging {
+    chunks: Vector<Vec<u8>>,
+    /// The cost of storing one byte on the trie.
+    byte_cost: Balance,
+    /// The only account that can add chunks. It pays for their storage.
+    uploader_id: AccountId,
+    /// The deposit paid for the storage of the chunks.
+    deposit: Balance,
+}
+
+impl BlobStaging {
+    /// Create a staging without chunks. Use `id` as a unique identifier on the trie, accept chunks
+    /// only from `uploader_id` and charge `byte_cost` for each byte of the storage they use.
+    pub fn new(id: Vec<u8>, byte_cost: Balance, uploader_id: AccountId) -> Self {
+        Self { chunks: Vector::new(id), byte_cost, uploader_id, deposit: 0 }
+    }
+
+    /// Returns the account that can add chunks.
+    pub fn uploader_id(&self) -> &AccountId {
+        &self.uploader_id
+    }
+
+    /// Returns the number of the staged chunks.
+    pub fn len(&self) -> u64 {
+        self.chunks.len()
+    }
+
+    /// Returns `true` if no chunks are staged.
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// Stores the chunk with the given index. Chunks should be added in order, starting from 0.
+    /// Adding the same chunk again does nothing, so that the upload can be retried. The storage is
+    /// paid with the attached deposit, and the rest of the deposit is refunded.
+    pub fn add_chunk(&mut self, index: u64, chunk: &[u8]) {
+        let predecessor = env::predecessor_account_id();
+        if predecessor != self.uploader_id {
+            env::panic(
+                format!("Only `{}` can add chunks to the staged blob.", self.uploader_id)
+                    .as_bytes(),
+            );
+        }
+        let attached_deposit = env::attached_deposit();
+        if index < self.chunks.len() {
+            if self.chunks.get_raw(index).as_deref() != Some(chunk) {
+                env::panic(format!("Chunk {} is already staged.", index).as_bytes());
+            }
+            refund(&predecessor, attached_deposit);
+            return;
+        }
+        if index > self.chunks.len() {
+            env::panic(
+                format!("Expected chunk {}, but got chunk {}.", self.chunks.len(), index)
+                    .as_bytes(),
+            );
+        }
+        let initial_storage_usage = env::storage_usage();
+        self.chunks.push_raw(chunk);
+        let cost = Balance::from(env::storage_usage() - initial_storage_usage) * self.byte_cost;
+        if attached_deposit < cost {
+            env::panic(
+                format!(
+                    "The attached deposit {} is not enough to pay for the storage of the chunk, \
+                     {} is required.",
+                    attached_deposit, cost
+                )
+                .as_bytes(),
+            );
+        }
+        self.deposit += cost;
+        refund(&predecessor, attached_deposit - cost);
+    }
+
+    /// Stores the chunk after the last staged one and returns its index. Useful when the chunk is
+    /// passed as the raw input of the method.
+    pub fn push_chunk(&mut self, chunk: &[u8]) -> u64 {
+        let index = self.chunks.len();
+        self.add_chunk(index, chunk);
+        index
+    }
+
+    /// Returns the blob assembled from the staged chunks, if its `sha256` hash is `expected_hash`,
+    /// and panics otherwise. The chunks are removed and the deposit is refunded.
+    pub fn take(&mut self, expected_hash: &[u8]) -> Vec<u8> {
+        if self.chunks.is_empty() {
+            env::panic(ERR_EMPTY_BLOB);
+        }
+        let blob: Vec<u8> = self.chunks.iter_raw().flatten().collect();
+        if env::sha256(&blob) != expected_hash {
+            env::panic(ERR_HASH_MISMATCH);
+        }
+        self.clear();
+        blob
+    }
+
+    /// Removes the staged chunks and refunds the deposit.
+    pub fn clear(&mut self) {
+        while self.chunks.pop_raw().is_some() {}
+        refund(&self.uploader_id, self.deposit);
+        self.deposit = 0;
+    }
+}
+
+/// Transfers `amount` back to the account, if it is not zero.
+fn refund(account_id: &AccountId, amount: Balance) {
+    if amount > 0 {
+        Promise::new(account_id.clone()).transfer(amount);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collections::BlobStaging;
+    use crate::{env, MockedBlockchain};
+    use near_vm_logic::types::Balance;
+    use near_vm_logic::VMContext;
+
+    fn set_env(predecessor: &str, attached_deposit: Balance) {
+        let storage = match env::take_blockchain_interface() {
+            Some(mut bi) => bi.as_mut_mocked_blockchain().unwrap().take_storage(),
+            None => Default::default(),
+        };
+        let context = VMContext {
+            current_account_id: "alice.near".to_string(),
+            signer_account_id: "bob.near".to_string(),
+            signer_account_pk: vec![0, 1, 2],
+            predecessor_account_id: predecessor.to_string(),
+            input: vec![],
+            block_index: 0,
+            block_timestamp: 0,
+            account_balance: 10u128.pow(6),
+            account_locked_balance: 0,
+            storage_usage: 10u64.pow(6),
+            attached_deposit,
+            prepaid_gas: 10u64.pow(18),
+            random_seed: vec![0, 1, 2],
+            is_view: false,
+            output_data_receivers: vec![],
+            epoch_height: 0,
+        };
+        env::set_blockchain_interface(Box::new(MockedBlockchain::new(
+            context,
+            Default::default(),
+            Default::default(),
+            vec![],
+            storage,
+        )));
+    }
+
+    /// Returns the amounts transferred by the current call.
+    fn refunds() -> Vec<String> {
+        env::created_receipts()
+            .iter()
+            .map(|receipt| format!("{:?}", receipt))
+            .filter(|receipt| receipt.contains("Transfer"))
+            .collect()
+    }
+
+    #[test]
+    pub fn test_add_and_take() {
+        set_env("bob.near", 1_000);
+        let mut blob = BlobStaging::new(b"b".to_vec(), 1, "bob.near".to_string());
+        blob.add_chunk(0, &[1, 2, 3]);
+        assert_eq!(refunds().len(), 1);
+        set_env("bob.near", 1_000);
+        assert_eq!(blob.push_chunk(&[4, 5]), 1);
+        // Retrying the upload of the same chunk does nothing.
+        set_env("bob.near", 0);
+        blob.add_chunk(1, &[4, 5]);
+        assert_eq!(blob.len(), 2);
+        let deposit = blob.deposit;
+        assert!(deposit > 0);
+
+        set_env("carol.near", 0);
+        let hash = env::sha256(&[1, 2, 3, 4, 5]);
+        assert_eq!(blob.take(&hash), vec![1, 2, 3, 4, 5]);
+        assert!(blob.is_empty());
+        assert_eq!(blob.deposit, 0);
+        let refunds = refunds();
+        assert_eq!(refunds.len(), 1);
+        assert!(refunds[0].contains("\"bob.near\""));
+        assert!(refunds[0].contains(&format!("deposit: {}", deposit)));
+    }
+
+    #[test]
+    #[should_panic(expected = "The hash of the staged blob does not match the expected hash.")]
+    pub fn test_hash_mismatch() {
+        set_env("bob.near", 1_000);
+        let mut blob = BlobStaging::new(b"b".to_vec(), 1, "bob.near".to_string());
+        blob.add_chunk(0, &[1, 2, 3]);
+        blob.take(&env::sha256(&[1, 2]));
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected chunk 1, but got chunk 2.")]
+    pub fn test_chunk_out_of_order() {
+        set_env("bob.near", 1_000);
+        let mut blob = BlobStaging::new(b"b".to_vec(), 1, "bob.near".to_string());
+        blob.add_chunk(0, &[1, 2, 3]);
+        blob.add_chunk(2, &[1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Chunk 0 is already staged.")]
+    pub fn test_chunk_replaced() {
+        set_env("bob.near", 1_000);
+        let mut blob = BlobStaging::new(b"b".to_vec(), 1, "bob.near".to_string());
+        blob.add_chunk(0, &[1, 2, 3]);
+        blob.add_chunk(0, &[1, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "is not enough to pay for the storage of the chunk")]
+    pub fn test_not_enough_deposit() {
+        set_env("bob.near", 1);
+        let mut blob = BlobStaging::new(b"b".to_vec(), 1, "bob.near".to_string());
+        blob.add_chunk(0, &[1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only `bob.near` can add chunks to the staged blob.")]
+    pub fn test_other_uploader() {
+        // Other accounts can not take the first chunk before the uploader.
+        set_env("carol.near", 1_000);
+        let mut blob = BlobStaging::new(b"b".to_vec(), 1, "bob.near".to_string());
+        blob.add_chunk(0, &[1, 2, 3]);
+    }
+
+    #[test]
+    pub fn test_clear() {
+        set_env("bob.near", 1_000);
+        let mut blob = BlobStaging::new(b"b".to_vec(), 1, "bob.near".to_string());
+        blob.add_chunk(0, &[1, 2, 3]);
+        set_env("bob.near", 0);
+        blob.clear();
+        assert!(blob.is_empty());
+        assert_eq!(refunds().len(), 1);
+        assert!(env::storage_read(&[b'b', 0, 0, 0, 0, 0, 0, 0, 0]).is_none());
+    }
+}
@@ -0,0 +1,179 @@
+//! A value stored on the trie under its own key, that is read only when it is accessed and written
+//! only when it is changed.
+use crate::env;
+use borsh::{BorshDeserialize, BorshSerialize};
+use std::cell::OnceCell;
+use std::ops::{Deref, DerefMut};
+
+const ERR_VALUE_NOT_FOUND: &[u8] = b"The lazy value is not found in the storage.";
+const ERR_VALUE_DESERIALIZATION: &[u8] = b"Cannot deserialize the lazy value";
+const ERR_VALUE_SERIALIZATION: &[u8] = b"Cannot serialize the lazy value";
+
+/// A value that is read from the trie on the first access and is written back only if it was
+/// modified. Used for the fields of the contracts with `#[near_bindgen(lazy_state)]`, so that a
+/// method call only reads and writes the fields that it uses.
+pub struct Lazy<T> {
+    /// The key the value was read from, `None` for the values created with `Lazy::new`.
+    key: Option<Vec<u8>>,
+    value: OnceCell<T>,
+    is_dirty: bool,
+}
+
+impl<T> Lazy<T> {
+    /// Create a new value that is not stored yet.
+    pub fn new(value: T) -> Self {
+        Self { key: None, value: OnceCell::from(value), is_dirty: true }
+    }
+
+    /// Create a value that is stored under `key`. It is read from the storage on the first access.
+    pub fn from_key(key: Vec<u8>) -> Self {
+        Self { key: Some(key), value: OnceCell::new(), is_dirty: false }
+    }
+
+    /// Replaces the value without reading the old one from the storage.
+    pub fn set(&mut self, value: T) {
+        self.value = OnceCell::from(value);
+        self.is_dirty = true;
+    }
+}
+
+impl<T: BorshDeserialize> Lazy<T> {
+    /// Returns the value, reading it from the storage if it was not read yet.
+    pub fn get(&self) -> &T {
+        self.value.get_or_init(|| {
+            let key = self.key.as_ref().expect("Value is created either with a key or a value");
+            let raw_value =
+                env::storage_read(key).unwrap_or_else(|| env::panic(ERR_VALUE_NOT_FOUND));
+            T::try_from_slice(&raw_value).unwrap_or_else(|_| env::panic(ERR_VALUE_DESERIALIZATION))
+        })
+    }
+
+    /// Returns the mutable value, reading it from the storage if it was not read yet. The value is
+    /// written on the next `flush`.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.get();
+        self.is_dirty = true;
+        self.value.get_mut().unwrap()
+    }
+}
+
+impl<T: BorshSerialize> Lazy<T> {
+    /// Writes the value under `key` if it was modified or if it was not stored under this key.
+    /// Does nothing if the value was never read.
+    pub fn flush(&self, key: &[u8]) {
+        if let Some(value) = self.value.get() {
+            if self.is_dirty || self.key.as_deref() != Some(key) {
+                let raw_value =
+                    value.try_to_vec().unwrap_or_else(|_| env::panic(ERR_VALUE_SERIALIZATION));
+                env::storage_write(key, &raw_value);
+            }
+        }
+    }
+}
+
+impl<T: BorshDeserialize> Deref for Lazy<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.get()
+    }
+}
+
+impl<T: BorshDeserialize> DerefMut for Lazy<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.get_mut()
+    }
+}
+
+impl<T: Default> Default for Lazy<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> From<T> for Lazy<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collections::Lazy;
+    use crate::{env, MockedBlockchain};
+    use near_vm_logic::VMContext;
+
+    fn set_env() {
+        let context = VMContext {
+            current_account_id: "alice.near".to_string(),
+            signer_account_id: "bob.near".to_string(),
+            signer_account_pk: vec![0, 1, 2],
+            predecessor_account_id: "carol.near".to_string(),
+            input: vec![],
+            block_index: 0,
+            block_timestamp: 0,
+            account_balance: 0,
+            account_locked_balance: 0,
+            storage_usage: 10u64.pow(6),
+            attached_deposit: 0,
+            prepaid_gas: 10u64.pow(18),
+            random_seed: vec![0, 1, 2],
+            is_view: false,
+            output_data_receivers: vec![],
+            epoch_height: 0,
+        };
+        env::set_blockchain_interface(Box::new(MockedBlockchain::new(
+            context,
+            Default::default(),
+            Default::default(),
+            vec![],
+            Default::default(),
+        )));
+    }
+
+    #[test]
+    pub fn test_write_read() {
+        set_env();
+        let value = Lazy::new(vec![1u8, 2, 3]);
+        value.flush(b"v");
+        let mut value: Lazy<Vec<u8>> = Lazy::from_key(b"v".to_vec());
+        assert_eq!(*value, vec![1, 2, 3]);
+        value.push(4);
+        value.flush(b"v");
+        let value: Lazy<Vec<u8>> = Lazy::from_key(b"v".to_vec());
+        assert_eq!(*value, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    pub fn test_flush_only_modified() {
+        set_env();
+        Lazy::new(1u64).flush(b"v");
+        let value: Lazy<u64> = Lazy::from_key(b"v".to_vec());
+        env::storage_write(b"v", &2u64.to_le_bytes());
+        // The value was not read, so it is not written.
+        value.flush(b"v");
+        assert_eq!(*value, 2);
+        // The value was read, but not modified.
+        env::storage_write(b"v", &3u64.to_le_bytes());
+        value.flush(b"v");
+        assert_eq!(env::storage_read(b"v").unwrap(), 3u64.to_le_bytes().to_vec());
+    }
+
+    #[test]
+    pub fn test_set_without_read() {
+        set_env();
+        let mut value: Lazy<u64> = Lazy::from_key(b"v".to_vec());
+        value.set(5);
+        value.flush(b"v");
+        let value: Lazy<u64> = Lazy::from_key(b"v".to_vec());
+        assert_eq!(*value, 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "The lazy value is not found in the storage.")]
+    pub fn test_missing_value() {
+        set_env();
+        let value: Lazy<u64> = Lazy::from_key(b"v".to_vec());
+        value.get();
+    }
+}
@@ -0,0 +1,15 @@
+//! The state of the contract that is read before a method is called and written after it.
+
+/// The state of the contract. Implemented by `#[near_bindgen]` for the contract struct: by default
+/// the struct is stored as a whole, and with `#[near_bindgen(lazy_state)]` each field, which
+/// should be `collections::Lazy`, is stored under its own key. The key of the state, `STATE` by
+/// default, can be changed with `#[near_bindgen(state_key = "...")]`. With
+/// `#[near_bindgen(state_version = N)]` reading an existing state panics unless its stored version
+/// is `N`, and writing the state stores the version.
+pub trait ContractState: Sized {
+    /// Read the state, or return `None` if the contract does not have the state yet.
+    fn state_read() -> Option<Self>;
+
+    /// Write the state.
+    fn state_write(&self);
+}
@@ -22,6 +22,12 @@ thread_local! {
          = RefCell::new(None);
 }
 
+#[cfg(any(debug_assertions, not(target_arch = "wasm32")))]
+thread_local! {
+    /// Whether a view method is being executed, see `ViewGuard`.
+    static IS_VIEW: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
 const BLOCKCHAIN_INTERFACE_NOT_SET_ERR: &str = "Blockchain interface not set.";
 const NOT_MOCKED_BLOCKCHAIN_ERR: &str =
     "Operation expects mocked blockchain, e.g. because it can be only called from unit tests.";
@@ -39,6 +45,8 @@ const EVICTED_REGISTER: u64 = std::u64::MAX - 1;
 
 /// Key used to store the state of the contract.
 const STATE_KEY: &[u8] = b"STATE";
+/// Key used to store the version of the contract state layout.
+const STATE_VERSION_KEY: &[u8] = b"STATE_VERSION";
 
 /// The minimum length of a valid account ID.
 const MIN_ACCOUNT_ID_LEN: u64 = 2;
@@ -110,6 +118,48 @@ pub fn take_blockchain_interface() -> Option<Box<dyn BlockchainInterface>> {
     BLOCKCHAIN_INTERFACE.with(|b| b.replace(None))
 }
 
+/// Marks the execution of a view method. While the guard is alive, writing to and removing from
+/// the storage, and creating promises panic. `#[near_bindgen]` creates it in the methods marked
+/// with `#[view]` and in their wrappers. The checks are done with the mocked blockchain and when
+/// near-sdk is compiled with debug assertions, otherwise the guard does nothing.
+#[doc(hidden)]
+pub struct ViewGuard {
+    #[cfg(any(debug_assertions, not(target_arch = "wasm32")))]
+    was_view: bool,
+}
+
+impl ViewGuard {
+    #[cfg(any(debug_assertions, not(target_arch = "wasm32")))]
+    pub fn enter() -> Self {
+        Self { was_view: IS_VIEW.with(|v| v.replace(true)) }
+    }
+
+    #[cfg(all(not(debug_assertions), target_arch = "wasm32"))]
+    pub fn enter() -> Self {
+        Self {}
+    }
+}
+
+#[cfg(any(debug_assertions, not(target_arch = "wasm32")))]
+impl Drop for ViewGuard {
+    fn drop(&mut self) {
+        IS_VIEW.with(|v| v.set(self.was_view));
+    }
+}
+
+/// Panics if `operation` is performed from a view method.
+#[cfg(any(debug_assertions, not(target_arch = "wasm32")))]
+fn assert_not_view(operation: &str) {
+    IS_VIEW.with(|v| {
+        if v.get() {
+            panic(format!("{} is not allowed in a view method.", operation).as_bytes())
+        }
+    });
+}
+
+#[cfg(all(not(debug_assertions), target_arch = "wasm32"))]
+fn assert_not_view(_operation: &str) {}
+
 /// Implements panic hook that converts `PanicInfo` into a string and provides it through the
 /// blockchain interface.
 fn panic_hook_impl(info: &std_panic::PanicInfo) {
@@ -319,6 +369,7 @@ pub fn promise_create(
     amount: Balance,
     gas: Gas,
 ) -> PromiseIndex {
+    assert_not_view("Creating a promise");
     let account_id = account_id.as_bytes();
     unsafe {
         BLOCKCHAIN_INTERFACE.with(|b| {
@@ -344,6 +395,7 @@ pub fn promise_then(
     amount: Balance,
     gas: Gas,
 ) -> PromiseIndex {
+    assert_not_view("Creating a promise");
     let account_id = account_id.as_bytes();
     unsafe {
         BLOCKCHAIN_INTERFACE.with(|b| {
@@ -363,6 +415,7 @@ pub fn promise_then(
 }
 /// Creates a new promise which completes when time all promises passed as arguments complete.
 pub fn promise_and(promise_indices: &[PromiseIndex]) -> PromiseIndex {
+    assert_not_view("Creating a promise");
     let mut data = vec![0u8; promise_indices.len() * size_of::<PromiseIndex>()];
     for i in 0..promise_indices.len() {
         data[i * size_of::<PromiseIndex>()..(i + 1) * size_of::<PromiseIndex>()]
@@ -378,6 +431,7 @@ pub fn promise_and(promise_indices: &[PromiseIndex]) -> PromiseIndex {
     }
 }
 pub fn promise_batch_create(account_id: &AccountId) -> PromiseIndex {
+    assert_not_view("Creating a promise");
     unsafe {
         BLOCKCHAIN_INTERFACE.with(|b| {
             b.borrow()
@@ -388,6 +442,7 @@ pub fn promise_batch_create(account_id: &AccountId) -> PromiseIndex {
     }
 }
 pub fn promise_batch_then(promise_index: PromiseIndex, account_id: &AccountId) -> PromiseIndex {
+    assert_not_view("Creating a promise");
     unsafe {
         BLOCKCHAIN_INTERFACE.with(|b| {
             b.borrow().as_ref().expect(BLOCKCHAIN_INTERFACE_NOT_SET_ERR).promise_batch_then(
@@ -635,6 +690,7 @@ pub fn log(message: &[u8]) {
 /// Writes key-value into storage.
 /// If another key-value existed in the storage with the same key it returns `true`, otherwise `false`.
 pub fn storage_write(key: &[u8], value: &[u8]) -> bool {
+    assert_not_view("Writing to the storage");
     match unsafe {
         BLOCKCHAIN_INTERFACE.with(|b| {
             b.borrow().as_ref().expect(BLOCKCHAIN_INTERFACE_NOT_SET_ERR).storage_write(
@@ -670,6 +726,7 @@ pub fn storage_read(key: &[u8]) -> Option<Vec<u8>> {
 /// Removes the value stored under the given key.
 /// If key-value existed returns `true`, otherwise `false`.
 pub fn storage_remove(key: &[u8]) -> bool {
+    assert_not_view("Removing from the storage");
     match unsafe {
         BLOCKCHAIN_INTERFACE.with(|b| {
             b.borrow().as_ref().expect(BLOCKCHAIN_INTERFACE_NOT_SET_ERR).storage_remove(
@@ -721,13 +778,24 @@ pub fn created_receipts() -> Vec<Receipt> {
 // ############################################
 /// Load the state of the given object.
 pub fn state_read<T: borsh::BorshDeserialize>() -> Option<T> {
-    storage_read(STATE_KEY)
-        .map(|data| T::try_from_slice(&data).expect("Cannot deserialize the contract state."))
+    state_read_at(STATE_KEY)
 }
 
 pub fn state_write<T: borsh::BorshSerialize>(state: &T) {
+    state_write_at(STATE_KEY, state)
+}
+
+/// Load the state of the given object stored under `key` instead of the default key. Allows the
+/// contract to keep several parts of the state separately, e.g. the configuration and the data.
+pub fn state_read_at<T: borsh::BorshDeserialize>(key: &[u8]) -> Option<T> {
+    storage_read(key)
+        .map(|data| T::try_from_slice(&data).expect("Cannot deserialize the contract state."))
+}
+
+/// Save the state of the given object under `key` instead of the default key.
+pub fn state_write_at<T: borsh::BorshSerialize>(key: &[u8], state: &T) {
     let data = state.try_to_vec().expect("Cannot serialize the contract state.");
-    storage_write(STATE_KEY, &data);
+    storage_write(key, &data);
 }
 
 /// Returns `true` if the contract state exists and `false` otherwise.
@@ -735,6 +803,42 @@ pub fn state_exists() -> bool {
     storage_has_key(STATE_KEY)
 }
 
+/// Removes the contract state stored under the default key. Returns `true` if it existed.
+pub fn state_remove() -> bool {
+    storage_remove(STATE_KEY)
+}
+
+/// Returns the version of the contract state layout, or 0 if the version was never stored.
+pub fn state_version() -> u32 {
+    storage_read(STATE_VERSION_KEY)
+        .map(|data| {
+            <u32 as borsh::BorshDeserialize>::try_from_slice(&data)
+                .expect("Cannot deserialize the state version.")
+        })
+        .unwrap_or(0)
+}
+
+/// Stores the version of the contract state layout.
+pub fn set_state_version(version: u32) {
+    storage_write(STATE_VERSION_KEY, &version.to_le_bytes());
+}
+
+/// Panics if the stored version of the contract state layout is not `expected`, i.e. the state
+/// was written by another version of the contract and should be migrated first.
+pub fn assert_state_version(expected: u32) {
+    let version = state_version();
+    if version != expected {
+        panic(
+            format!(
+                "The contract state has version {}, but version {} is expected. Migrate the state \
+                 first.",
+                version, expected
+            )
+            .as_bytes(),
+        )
+    }
+}
+
 // ##################
 // # Helper methods #
 // ##################
@@ -772,6 +876,105 @@ pub fn is_valid_account_id(account_id: &[u8]) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::MockedBlockchain;
+    use near_vm_logic::VMContext;
+
+    fn set_env() {
+        let context = VMContext {
+            current_account_id: "alice.near".to_string(),
+            signer_account_id: "bob.near".to_string(),
+            signer_account_pk: vec![0, 1, 2],
+            predecessor_account_id: "carol.near".to_string(),
+            input: vec![],
+            block_index: 0,
+            block_timestamp: 0,
+            account_balance: 0,
+            account_locked_balance: 0,
+            storage_usage: 10u64.pow(6),
+            attached_deposit: 0,
+            prepaid_gas: 10u64.pow(18),
+            random_seed: vec![0, 1, 2],
+            is_view: false,
+            output_data_receivers: vec![],
+            epoch_height: 0,
+        };
+        set_blockchain_interface(Box::new(MockedBlockchain::new(
+            context,
+            Default::default(),
+            Default::default(),
+            vec![],
+            Default::default(),
+        )));
+    }
+
+    #[test]
+    fn test_state_at() {
+        set_env();
+        state_write(&1u64);
+        state_write_at(b"CONFIG", &"config".to_string());
+        assert_eq!(state_read::<u64>(), Some(1));
+        assert_eq!(state_read_at::<String>(b"CONFIG"), Some("config".to_string()));
+        assert_eq!(state_read_at::<u64>(b"DATA"), None);
+    }
+
+    #[test]
+    fn test_state_version() {
+        set_env();
+        assert_eq!(state_version(), 0);
+        set_state_version(2);
+        assert_eq!(state_version(), 2);
+        assert_state_version(2);
+        state_write(&1u64);
+        assert!(state_remove());
+        assert!(!state_exists());
+    }
+
+    #[test]
+    #[should_panic(expected = "The contract state has version 1, but version 2 is expected.")]
+    fn test_state_version_mismatch() {
+        set_env();
+        set_state_version(1);
+        assert_state_version(2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Writing to the storage is not allowed in a view method.")]
+    fn test_view_guard_storage_write() {
+        set_env();
+        let _guard = ViewGuard::enter();
+        storage_write(b"key", b"value");
+    }
+
+    #[test]
+    #[should_panic(expected = "Creating a promise is not allowed in a view method.")]
+    fn test_view_guard_promise() {
+        set_env();
+        let _guard = ViewGuard::enter();
+        promise_batch_create(&"bob.near".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Creating a promise is not allowed in a view method.")]
+    fn test_view_guard_returned_promise() {
+        set_env();
+        // The promise is scheduled when dropped, before the guard that was created first.
+        let _guard = ViewGuard::enter();
+        let _result = crate::Promise::new("bob.near".to_string());
+    }
+
+    #[test]
+    fn test_view_guard_scope() {
+        set_env();
+        {
+            let _guard = ViewGuard::enter();
+            {
+                let _inner = ViewGuard::enter();
+            }
+            assert!(storage_read(b"key").is_none());
+        }
+        storage_write(b"key", b"value");
+        assert!(storage_remove(b"key"));
+    }
 
     #[test]
     fn test_is_valid_account_id_strings() {
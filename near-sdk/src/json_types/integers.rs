@@ -3,12 +3,23 @@
 //! NOTE: JSON standard can only work with integer up to 53 bits. So we need helper classes for
 //! 64-bit and 128-bit integers.
 
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 macro_rules! impl_str_type {
     ($iden: ident, $ty: tt) => {
-        #[derive(Debug, Clone, Copy, PartialEq, BorshDeserialize, BorshSerialize)]
+        #[derive(
+            Debug,
+            Clone,
+            Copy,
+            PartialEq,
+            Eq,
+            PartialOrd,
+            Ord,
+            BorshDeserialize,
+            BorshSerialize,
+            BorshSchema,
+        )]
         pub struct $iden(pub $ty);
 
         impl From<$ty> for $iden {
@@ -117,4 +128,10 @@ mod tests {
         test_serde!(I64, i64, i64::max_value());
         test_serde!(I64, i64, i64::min_value());
     }
+
+    #[test]
+    fn test_compare() {
+        assert!(U128(1) < U128(2));
+        assert!(I64(-1) < I64(0));
+    }
 }
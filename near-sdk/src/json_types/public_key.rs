@@ -1,4 +1,4 @@
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use serde::{Deserialize, Serialize};
 use std::convert::{TryFrom, TryInto};
 
@@ -25,7 +25,7 @@ impl TryFrom<String> for CurveType {
 
 /// Public key in a binary format with base58 string serialization with human-readable curve.
 /// e.g. `ed25519:3tysLvy7KGoE8pznUgXvSHa4vYyGvrDZFcT8jgb8PEQ6`
-#[derive(Clone, PartialEq, PartialOrd, Ord, Eq, BorshDeserialize, BorshSerialize)]
+#[derive(Clone, PartialEq, PartialOrd, Ord, Eq, BorshDeserialize, BorshSerialize, BorshSchema)]
 pub struct Base58PublicKey(pub Vec<u8>);
 
 impl Base58PublicKey {
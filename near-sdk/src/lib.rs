@@ -8,19 +8,41 @@ mod environment;
 pub use environment::env;
 
 mod promise;
-pub use promise::{Promise, PromiseOrValue};
+pub use promise::{
+    Callback, CallbackInput, CallbackVec, FunctionCallBuilder, NoCallback, Promise, PromiseOrValue,
+    TypedPromise, DEFAULT_FUNCTION_CALL_GAS,
+};
 
 mod metadata;
-pub use metadata::{Metadata, MethodMetadata};
+pub use metadata::{AccessRestriction, CallbackMetadata, Metadata, MethodMetadata};
+
+mod contract_state;
+pub use contract_state::ContractState;
 
 pub mod json_types;
 
+pub mod abi;
+
+pub mod borsh_json;
+
+pub mod upgrade;
+
+pub mod client;
+
 pub use environment::mocked_blockchain::MockedBlockchain;
 pub use near_runtime_fees::RuntimeFeesConfig;
 pub use near_vm_logic::types::*;
 pub use near_vm_logic::VMConfig;
 pub use near_vm_logic::VMContext;
 
+// Used by the code generated for `#[serializer(msgpack)]` and `#[serializer(cbor)]`.
+#[cfg(feature = "msgpack")]
+#[doc(hidden)]
+pub use rmp_serde;
+#[cfg(feature = "cbor")]
+#[doc(hidden)]
+pub use serde_cbor;
+
 #[macro_export]
 macro_rules! testing_env {
     ($context:expr, $config:expr, $fee_config:expr) => {
@@ -1,6 +1,7 @@
+use crate::abi::SerializerType;
 use borsh::{schema::BorshSchemaContainer, BorshDeserialize, BorshSchema, BorshSerialize};
 /// Version of the metadata format.
-const METADATA_SEMVER: [u32; 3] = [0, 1, 0];
+const METADATA_SEMVER: [u32; 3] = [0, 3, 0];
 
 /// Metadata of the contract.
 #[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq)]
@@ -21,16 +22,46 @@ impl Metadata {
 #[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq)]
 pub struct MethodMetadata {
     pub name: String,
+    /// Doc comments of the method, if any.
+    pub docs: Option<String>,
     /// Whether method does not modify the state.
     pub is_view: bool,
     /// Whether method can be used to initialize the state.
     pub is_init: bool,
+    /// Whether method accepts attached deposit.
+    pub is_payable: bool,
+    /// Which accounts can call the method.
+    pub access: AccessRestriction,
+    /// The serializer of the arguments.
+    pub input_serializer: SerializerType,
+    /// Whether the JSON arguments can also be passed as a positional array.
+    pub positional_args: bool,
     /// Schema of the arguments of the method.
     pub args: Option<BorshSchemaContainer>,
     /// Schemas for each callback of the method.
-    pub callbacks: Vec<BorshSchemaContainer>,
+    pub callbacks: Vec<CallbackMetadata>,
     /// If all callbacks have the same type then this field can be used instead.
-    pub callbacks_vec: Option<BorshSchemaContainer>,
+    pub callbacks_vec: Option<CallbackMetadata>,
+    /// The serializer of the return value.
+    pub result_serializer: SerializerType,
     /// Schema of the return type.
     pub result: Option<BorshSchemaContainer>,
 }
+
+/// Metadata of the result of a promise that is passed to the method as a callback argument.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq)]
+pub struct CallbackMetadata {
+    /// The serializer of the promise result.
+    pub serializer: SerializerType,
+    /// Schema of the promise result.
+    pub schema: BorshSchemaContainer,
+}
+
+/// Accounts that are allowed to call a method.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccessRestriction {
+    /// Any account can call the method.
+    Public,
+    /// Only the contract itself can call the method, e.g. a migration.
+    SelfOnly,
+}
@@ -3,6 +3,7 @@ use near_vm_logic::types::{AccountId, Balance, Gas, PromiseIndex, PublicKey};
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::io::{Error, Write};
+use std::marker::PhantomData;
 use std::rc::Rc;
 
 pub enum PromiseAction {
@@ -163,7 +164,7 @@ impl PromiseJoint {
 /// #[near_bindgen]
 /// impl ContractA {
 ///     pub fn a(&self) -> Promise {
-///         contract_b::b(&"bob_near".to_string(), 0, 1_000)
+///         contract_b::b().with_gas(1_000).on("bob_near")
 ///     }
 /// }
 /// ```
@@ -185,7 +186,8 @@ pub struct Promise {
     should_return: RefCell<bool>,
 }
 
-/// Until we implement strongly typed promises we serialize them as unit struct.
+/// Untyped promises are described as unit struct. Use `TypedPromise` to expose the type of the
+/// value that the promise produces.
 impl BorshSchema for Promise {
     fn add_definitions_recursively(
         definitions: &mut HashMap<borsh::schema::Declaration, borsh::schema::Definition>,
@@ -304,11 +306,11 @@ impl Promise {
     /// let p3 = p1.and(p2);
     /// // p3.create_account();
     /// ```
-    pub fn and(self, other: Promise) -> Promise {
+    pub fn and<P: Into<Promise>>(self, other: P) -> Promise {
         Promise {
             subtype: PromiseSubtype::Joint(Rc::new(PromiseJoint {
                 promise_a: self,
-                promise_b: other,
+                promise_b: other.into(),
                 promise_index: RefCell::new(None),
             })),
             should_return: RefCell::new(false),
@@ -329,7 +331,8 @@ impl Promise {
     /// let p4 = Promise::new("eva_near".to_string()).create_account();
     /// p1.then(p2).and(p3).then(p4);
     /// ```
-    pub fn then(self, mut other: Promise) -> Promise {
+    pub fn then<P: Into<Promise>>(self, other: P) -> Promise {
+        let mut other = other.into();
         match &mut other.subtype {
             PromiseSubtype::Single(x) => *x.after.borrow_mut() = Some(self),
             PromiseSubtype::Joint(_) => panic!("Cannot callback joint promise."),
@@ -356,11 +359,11 @@ impl Promise {
     /// #[near_bindgen]
     /// impl ContractA {
     ///     pub fn a1(&self) {
-    ///        contract_b::b(&"bob_near".to_string(), 0, 1_000).as_return();
+    ///        contract_b::b().with_gas(1_000).on("bob_near").as_return();
     ///     }
     ///
     ///     pub fn a2(&self) -> Promise {
-    ///        contract_b::b(&"bob_near".to_string(), 0, 1_000)
+    ///        contract_b::b().with_gas(1_000).on("bob_near").into()
     ///     }
     /// }
     /// ```
@@ -397,6 +400,201 @@ impl serde::Serialize for Promise {
     }
 }
 
+/// Gas attached to a `FunctionCallBuilder` unless specified otherwise.
+pub const DEFAULT_FUNCTION_CALL_GAS: Gas = 10_000_000_000_000;
+
+/// A function call on another contract that is not scheduled yet. Functions generated by
+/// `ext_contract` return builders, so that deposit and gas can be attached only when needed, and
+/// the call is scheduled once the receiving account is known:
+/// ```ignore
+/// ext_status_message::set_status(message)
+///     .with_deposit(1_000)
+///     .with_gas(SINGLE_CALL_GAS)
+///     .on(&account_id);
+/// ```
+/// By default the call has no deposit and `DEFAULT_FUNCTION_CALL_GAS` gas attached.
+///
+/// `T` is the type of the value returned by the method, and `In` describes the results of the
+/// previous promises that the method reads with `#[callback]`, see `CallbackInput`.
+pub struct FunctionCallBuilder<T, In = NoCallback> {
+    method_name: Vec<u8>,
+    arguments: Vec<u8>,
+    deposit: Balance,
+    gas: Gas,
+    output: PhantomData<(T, In)>,
+}
+
+impl<T, In> FunctionCallBuilder<T, In> {
+    /// Create a call of the given method with already serialized arguments.
+    pub fn new(method_name: Vec<u8>, arguments: Vec<u8>) -> Self {
+        Self {
+            method_name,
+            arguments,
+            deposit: 0,
+            gas: DEFAULT_FUNCTION_CALL_GAS,
+            output: PhantomData,
+        }
+    }
+
+    /// Attach the given amount of tokens to the call.
+    pub fn with_deposit(mut self, deposit: Balance) -> Self {
+        self.deposit = deposit;
+        self
+    }
+
+    /// Attach the given amount of gas to the call.
+    pub fn with_gas(mut self, gas: Gas) -> Self {
+        self.gas = gas;
+        self
+    }
+
+    /// Schedule the call on the given account.
+    pub fn on<A: ToString + ?Sized>(self, account_id: &A) -> TypedPromise<T, In> {
+        TypedPromise::new(Promise::new(account_id.to_string()).function_call(
+            self.method_name,
+            self.arguments,
+            self.deposit,
+            self.gas,
+        ))
+    }
+}
+
+/// A `Promise` that eventually produces a value of type `T`, e.g. the return value of the remote
+/// method that it calls. Functions generated by `ext_contract` return `TypedPromise`s so that the
+/// type of the result is known to the caller and is exposed through the contract metadata.
+///
+/// In the following code `get_status` produces `TypedPromise<Option<String>>`, and since `then`
+/// returns the promise of the callback, `complex_call` can only be declared with the return type
+/// of the last method in the chain.
+///
+/// `In` describes the results of the previous promises that the called method reads with
+/// `#[callback]`, and `then` only accepts the callbacks that read the value produced by this
+/// promise, see `CallbackInput`. Promises joined with `and` and untyped promises are not checked.
+/// ```
+/// # use near_sdk::{ext_contract, near_bindgen, TypedPromise};
+/// # use borsh::{BorshDeserialize, BorshSerialize};
+/// #[ext_contract]
+/// pub trait ExtStatusMessage {
+///     fn set_status(&mut self, message: String);
+///     fn get_status(&self, account_id: String) -> Option<String>;
+/// }
+///
+/// #[near_bindgen]
+/// #[derive(Default, BorshDeserialize, BorshSerialize)]
+/// struct ContractA {}
+///
+/// #[near_bindgen]
+/// impl ContractA {
+///     pub fn complex_call(&mut self, account_id: String, message: String) -> TypedPromise<Option<String>> {
+///         ext_status_message::set_status(message)
+///             .on(&account_id)
+///             .then(ext_status_message::get_status("bob_near".to_string()).on(&account_id))
+///     }
+/// }
+/// ```
+pub struct TypedPromise<T, In = NoCallback> {
+    promise: Promise,
+    output: PhantomData<(T, In)>,
+}
+
+impl<T, In> TypedPromise<T, In> {
+    /// Declare that the given promise produces a value of type `T`.
+    pub fn new(promise: Promise) -> Self {
+        Self { promise, output: PhantomData }
+    }
+
+    /// Erase the type of the value produced by this promise.
+    pub fn into_promise(self) -> Promise {
+        self.promise
+    }
+
+    /// Merge this promise with another promise, see `Promise::and`. The joint promise produces
+    /// several values, so it is not typed.
+    pub fn and<P: Into<Promise>>(self, other: P) -> Promise {
+        self.promise.and(other)
+    }
+
+    /// Schedules execution of another promise right after the current promise finish executing,
+    /// see `Promise::then`. `other` should either read the value produced by this promise or not
+    /// read it at all. The result is the value produced by `other`.
+    pub fn then<U, I: CallbackInput<T>>(self, other: TypedPromise<U, I>) -> TypedPromise<U> {
+        TypedPromise::new(self.promise.then(other.promise))
+    }
+
+    /// Marks the promise as the one that should be considered as a return value, see
+    /// `Promise::as_return`.
+    pub fn as_return(self) -> Self {
+        Self::new(self.promise.as_return())
+    }
+}
+
+impl<T, In> From<TypedPromise<T, In>> for Promise {
+    fn from(promise: TypedPromise<T, In>) -> Self {
+        promise.promise
+    }
+}
+
+/// The promise is described by the schema of the value that it produces.
+impl<T, In> BorshSchema for TypedPromise<T, In>
+where
+    T: BorshSchema,
+{
+    fn add_definitions_recursively(
+        definitions: &mut HashMap<borsh::schema::Declaration, borsh::schema::Definition>,
+    ) {
+        T::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> borsh::schema::Declaration {
+        T::declaration()
+    }
+}
+
+impl<T, In> serde::Serialize for TypedPromise<T, In> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.promise.serialize(serializer)
+    }
+}
+
+/// Describes the results of the previous promises that a method reads with `#[callback]` and
+/// `#[callback_vec]`, and is implemented if the method can be called as a callback of a promise
+/// that produces a value of type `T`. `ext_contract` and `ext_self` builders are marked with:
+/// * `NoCallback` if the method does not read the results, so it can follow any promise;
+/// * `Callback<C>` if it reads a single result of type `C`, or several results of types `(A, B)`
+///   that can only be produced by promises joined with `and`;
+/// * `CallbackVec<Vec<C>>` if it reads all results of type `C`.
+///
+/// The result of a method that returns `PromiseOrValue<C>` or `TypedPromise<C>` is `C`.
+pub trait CallbackInput<T> {}
+
+/// Marks the calls of the methods that do not read the results of the previous promises.
+pub struct NoCallback;
+
+/// Marks the calls of the methods that read the results of the previous promises with
+/// `#[callback]`.
+pub struct Callback<C>(PhantomData<C>);
+
+/// Marks the calls of the methods that read the results of the previous promises with
+/// `#[callback_vec]`.
+pub struct CallbackVec<C>(PhantomData<C>);
+
+impl<T> CallbackInput<T> for NoCallback {}
+
+impl<T> CallbackInput<T> for Callback<T> {}
+
+impl<T> CallbackInput<PromiseOrValue<T>> for Callback<T> {}
+
+impl<T, In> CallbackInput<TypedPromise<T, In>> for Callback<T> {}
+
+impl<T> CallbackInput<T> for CallbackVec<Vec<T>> {}
+
+impl<T> CallbackInput<PromiseOrValue<T>> for CallbackVec<Vec<T>> {}
+
+impl<T, In> CallbackInput<TypedPromise<T, In>> for CallbackVec<Vec<T>> {}
+
 pub enum PromiseOrValue<T> {
     Promise(Promise),
     Value(T),
@@ -423,6 +621,12 @@ impl<T> From<Promise> for PromiseOrValue<T> {
     }
 }
 
+impl<T, In> From<TypedPromise<T, In>> for PromiseOrValue<T> {
+    fn from(promise: TypedPromise<T, In>) -> Self {
+        PromiseOrValue::Promise(promise.promise.as_return())
+    }
+}
+
 impl<T: serde::Serialize> serde::Serialize for PromiseOrValue<T> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -447,3 +651,132 @@ impl<T: borsh::BorshSerialize> borsh::BorshSerialize for PromiseOrValue<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{env, Callback, FunctionCallBuilder, MockedBlockchain, NoCallback};
+    use crate::{Promise, PromiseOrValue, TypedPromise};
+    use borsh::BorshSchema;
+    use near_vm_logic::VMContext;
+    use std::collections::HashMap;
+
+    fn set_env() {
+        let context = VMContext {
+            current_account_id: "alice.near".to_string(),
+            signer_account_id: "bob.near".to_string(),
+            signer_account_pk: vec![0, 1, 2],
+            predecessor_account_id: "bob.near".to_string(),
+            input: vec![],
+            block_index: 0,
+            block_timestamp: 0,
+            account_balance: 10u128.pow(6),
+            account_locked_balance: 0,
+            storage_usage: 10u64.pow(6),
+            attached_deposit: 0,
+            prepaid_gas: 10u64.pow(18),
+            random_seed: vec![0, 1, 2],
+            is_view: false,
+            output_data_receivers: vec![],
+            epoch_height: 0,
+        };
+        env::set_blockchain_interface(Box::new(MockedBlockchain::new(
+            context,
+            Default::default(),
+            Default::default(),
+            vec![],
+            Default::default(),
+        )));
+    }
+
+    fn call<T, In>(account_id: &str, method_name: &str) -> TypedPromise<T, In> {
+        FunctionCallBuilder::new(method_name.as_bytes().to_vec(), vec![]).on(account_id)
+    }
+
+    /// Checks that the current call created receipts with the given receivers, called methods and
+    /// the indices of the receipts that they wait for.
+    fn assert_receipts(expected: &[(&str, &str, &[u64])]) {
+        let receipts: Vec<String> =
+            env::created_receipts().iter().map(|receipt| format!("{:?}", receipt)).collect();
+        assert_eq!(receipts.len(), expected.len());
+        for (receipt, (receiver_id, method_name, after)) in receipts.iter().zip(expected) {
+            assert!(receipt.contains(&format!("receipt_indices: {:?}", after)), "{}", receipt);
+            assert!(receipt.contains(&format!("receiver_id: {:?}", receiver_id)), "{}", receipt);
+            let method_name = format!("method_name: {:?}", method_name.as_bytes());
+            assert!(receipt.contains(&method_name), "{}", receipt);
+        }
+    }
+
+    #[test]
+    pub fn test_schema() {
+        assert_eq!(TypedPromise::<u64>::declaration(), u64::declaration());
+        assert_eq!(
+            TypedPromise::<Option<String>, Callback<u64>>::declaration(),
+            Option::<String>::declaration()
+        );
+        let mut definitions = HashMap::new();
+        TypedPromise::<Option<String>>::add_definitions_recursively(&mut definitions);
+        let mut expected = HashMap::new();
+        Option::<String>::add_definitions_recursively(&mut expected);
+        assert_eq!(definitions, expected);
+    }
+
+    #[test]
+    pub fn test_into_promise() {
+        set_env();
+        let promise: Promise = call::<u64, NoCallback>("bob.near", "get").into();
+        assert!(!*promise.should_return.borrow());
+        drop(promise);
+        assert_receipts(&[("bob.near", "get", &[])]);
+    }
+
+    #[test]
+    pub fn test_into_promise_or_value() {
+        set_env();
+        let value: PromiseOrValue<u64> = call::<u64, NoCallback>("bob.near", "get").into();
+        match &value {
+            PromiseOrValue::Promise(promise) => assert!(*promise.should_return.borrow()),
+            PromiseOrValue::Value(_) => panic!("Expected a promise."),
+        }
+        assert_eq!(serde_json::to_string(&value).unwrap(), "null");
+        drop(value);
+        assert_receipts(&[("bob.near", "get", &[])]);
+    }
+
+    #[test]
+    pub fn test_then() {
+        set_env();
+        let promise = call::<u64, NoCallback>("bob.near", "get")
+            .then(call::<u64, Callback<u64>>("alice.near", "on_get"))
+            .then(call::<(), NoCallback>("alice.near", "log"))
+            .as_return();
+        assert!(*promise.promise.should_return.borrow());
+        drop(promise);
+        assert_receipts(&[
+            ("bob.near", "get", &[]),
+            ("alice.near", "on_get", &[0]),
+            ("alice.near", "log", &[1]),
+        ]);
+    }
+
+    #[test]
+    pub fn test_and() {
+        set_env();
+        let promise = call::<u64, NoCallback>("bob.near", "get")
+            .and(call::<u64, NoCallback>("carol.near", "get"))
+            .then(call::<u64, NoCallback>("alice.near", "on_get_all"));
+        drop(promise);
+        assert_receipts(&[
+            ("bob.near", "get", &[]),
+            ("carol.near", "get", &[]),
+            ("alice.near", "on_get_all", &[0, 1]),
+        ]);
+    }
+
+    #[test]
+    pub fn test_serialize() {
+        set_env();
+        let promise = call::<u64, NoCallback>("bob.near", "get");
+        assert_eq!(serde_json::to_string(&promise).unwrap(), "null");
+        assert!(*promise.promise.should_return.borrow());
+    }
+}
@@ -0,0 +1,204 @@
+//! Upgrading the code of the contract by the contract itself.
+use crate::{env, Promise, DEFAULT_FUNCTION_CALL_GAS};
+use near_vm_logic::types::{AccountId, Gas};
+
+/// Key under which the hash of the code deployed by the last upgrade is stored.
+const CODE_HASH_KEY: &[u8] = b"CODE_HASH";
+
+/// Key under which the hash of the code being deployed is stored until the migration succeeds.
+const PENDING_CODE_HASH_KEY: &[u8] = b"PENDING_CODE_HASH";
+
+/// The method called after the new code is deployed unless specified otherwise.
+const DEFAULT_MIGRATE_METHOD: &[u8] = b"migrate";
+
+/// Deployment of new code on the current account, followed by the call of the migration method,
+/// e.g. the one marked with `#[migrate]`, in the same batch. If the migration fails, the code is
+/// not deployed either.
+/// ```ignore
+/// #[call]
+/// pub fn upgrade(&self) -> Promise {
+///     let args = serde_json::to_vec(&json!({ "owner_id": self.owner_id })).unwrap();
+///     Upgrade::from_input()
+///         .with_migration("migrate", args)
+///         .deploy(|account_id| account_id == &self.owner_id)
+/// }
+/// ```
+/// By default `migrate` method is called without arguments and with `DEFAULT_FUNCTION_CALL_GAS`
+/// gas attached.
+pub struct Upgrade {
+    code: Vec<u8>,
+    migrate_method: Option<Vec<u8>>,
+    migrate_args: Vec<u8>,
+    gas: Gas,
+}
+
+impl Upgrade {
+    /// Prepare the upgrade to the given code.
+    pub fn new(code: Vec<u8>) -> Self {
+        Self {
+            code,
+            migrate_method: Some(DEFAULT_MIGRATE_METHOD.to_vec()),
+            migrate_args: vec![],
+            gas: DEFAULT_FUNCTION_CALL_GAS,
+        }
+    }
+
+    /// Prepare the upgrade to the code passed as the raw input of the method.
+    pub fn from_input() -> Self {
+        Self::new(env::input().expect("Expected the new code of the contract as the input."))
+    }
+
+    /// Call the given migration method with the given arguments after the code is deployed.
+    pub fn with_migration(mut self, method_name: &str, arguments: Vec<u8>) -> Self {
+        self.migrate_method = Some(method_name.as_bytes().to_vec());
+        self.migrate_args = arguments;
+        self
+    }
+
+    /// Only deploy the code, e.g. if the layout of the state does not change.
+    pub fn without_migration(mut self) -> Self {
+        self.migrate_method = None;
+        self
+    }
+
+    /// Attach the given amount of gas to the migration call.
+    pub fn with_gas(mut self, gas: Gas) -> Self {
+        self.gas = gas;
+        self
+    }
+
+    /// Schedule the upgrade if `is_allowed` returns `true` for the predecessor account, and panic
+    /// otherwise. Logs the hashes of the old and the new code. The old hash is the one recorded by
+    /// the migration of the previous upgrade, see `record_code_hash`, so it is unknown if the
+    /// contract was never upgraded this way or was upgraded without migration.
+    pub fn deploy<F: FnOnce(&AccountId) -> bool>(self, is_allowed: F) -> Promise {
+        let predecessor = env::predecessor_account_id();
+        if !is_allowed(&predecessor) {
+            env::panic(
+                format!("Account `{}` is not allowed to upgrade the contract.", predecessor)
+                    .as_bytes(),
+            );
+        }
+        if self.code.is_empty() {
+            env::panic(b"The new code of the contract is empty.");
+        }
+        let old_hash = env::storage_read(CODE_HASH_KEY)
+            .map(|hash| bs58::encode(hash).into_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        let new_hash = env::sha256(&self.code);
+        env::log(
+            format!(
+                "Upgrading the contract code from {} to {}.",
+                old_hash,
+                bs58::encode(&new_hash).into_string()
+            )
+            .as_bytes(),
+        );
+        let promise = Promise::new(env::current_account_id()).deploy_contract(self.code);
+        match self.migrate_method {
+            Some(method_name) => {
+                // The batch may still fail, so the hash is recorded by the migration.
+                env::storage_write(PENDING_CODE_HASH_KEY, &new_hash);
+                promise.function_call(method_name, self.migrate_args, 0, self.gas)
+            }
+            None => {
+                // Nothing runs after the deployment to record the new hash.
+                env::storage_remove(CODE_HASH_KEY);
+                env::storage_remove(PENDING_CODE_HASH_KEY);
+                promise
+            }
+        }
+    }
+}
+
+/// Records the hash of the code deployed by `Upgrade` as the hash of the current code. It is
+/// called by the migration methods marked with `#[migrate]` or `#[init(migrate_from = ...)]`, which
+/// run in the same batch as the deployment, so the hash is only recorded if the upgrade succeeds.
+/// Other methods called with `Upgrade::with_migration` should call it themselves.
+pub fn record_code_hash() {
+    if let Some(hash) = env::storage_read(PENDING_CODE_HASH_KEY) {
+        env::storage_write(CODE_HASH_KEY, &hash);
+        env::storage_remove(PENDING_CODE_HASH_KEY);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::upgrade::{record_code_hash, Upgrade};
+    use crate::{env, MockedBlockchain};
+    use near_vm_logic::VMContext;
+
+    fn set_env(predecessor: &str, input: Vec<u8>) {
+        let context = VMContext {
+            current_account_id: "alice.near".to_string(),
+            signer_account_id: "bob.near".to_string(),
+            signer_account_pk: vec![0, 1, 2],
+            predecessor_account_id: predecessor.to_string(),
+            input,
+            block_index: 0,
+            block_timestamp: 0,
+            account_balance: 0,
+            account_locked_balance: 0,
+            storage_usage: 10u64.pow(6),
+            attached_deposit: 0,
+            prepaid_gas: 10u64.pow(18),
+            random_seed: vec![0, 1, 2],
+            is_view: false,
+            output_data_receivers: vec![],
+            epoch_height: 0,
+        };
+        env::set_blockchain_interface(Box::new(MockedBlockchain::new(
+            context,
+            Default::default(),
+            Default::default(),
+            vec![],
+            Default::default(),
+        )));
+    }
+
+    #[test]
+    fn test_deploy_and_migrate() {
+        set_env("bob.near", vec![1, 2, 3]);
+        Upgrade::from_input()
+            .with_migration("migrate_v2", b"{}".to_vec())
+            .with_gas(5)
+            .deploy(|account_id| account_id == "bob.near");
+        // Receipts contain `u128` deposits that `serde_json` cannot represent, so they are compared
+        // using `Debug`.
+        assert_eq!(
+            format!("{:?}", env::created_receipts()),
+            format!(
+                "[Receipt {{ receipt_indices: [], receiver_id: \"alice.near\", actions: [\
+                 DeployContract(DeployContractAction {{ code: [1, 2, 3] }}), \
+                 FunctionCall(FunctionCallAction {{ method_name: {:?}, args: {:?}, gas: 5, \
+                 deposit: 0 }})] }}]",
+                b"migrate_v2", b"{}"
+            )
+        );
+        // The hash is only recorded once the migration runs.
+        assert!(env::storage_read(b"CODE_HASH").is_none());
+        record_code_hash();
+        assert_eq!(env::storage_read(b"CODE_HASH").unwrap(), env::sha256(&[1, 2, 3]));
+        assert!(env::storage_read(b"PENDING_CODE_HASH").is_none());
+    }
+
+    #[test]
+    fn test_without_migration() {
+        set_env("bob.near", vec![]);
+        Upgrade::new(vec![1]).without_migration().deploy(|_| true);
+        assert_eq!(
+            format!("{:?}", env::created_receipts()),
+            "[Receipt { receipt_indices: [], receiver_id: \"alice.near\", actions: [\
+             DeployContract(DeployContractAction { code: [1] })] }]"
+        );
+        record_code_hash();
+        assert!(env::storage_read(b"CODE_HASH").is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Account `carol.near` is not allowed to upgrade the contract.")]
+    fn test_not_allowed() {
+        set_env("carol.near", vec![1, 2, 3]);
+        Upgrade::from_input().deploy(|account_id| account_id == "bob.near");
+    }
+}
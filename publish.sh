@@ -1,6 +1,6 @@
 #!/usr/bin/env bash
 set -ex
-for p in near-sdk-core near-sdk-macros near-sdk
+for p in near-sdk-core near-sdk-macros near-sdk near-sdk-abi
 do
 pushd ./${p}
 cargo publish